@@ -1,53 +1,182 @@
 use std::{fmt::Display, str::FromStr};
 
+use hierrorchy::{error_leaf, error_node};
 use proc_macro2::TokenStream as TokenStream2;
-use quote::ToTokens;
-use serde::Deserialize;
+use quote::{quote, ToTokens};
+use serde::{
+    de::{Error as _, MapAccess, Visitor},
+    Deserialize,
+};
+use std::error::Error;
+
+use crate::runtime_helpers::RuntimeHelper;
+
+const SCALAR_TYPES: [&str; 37] = [
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "NonZeroU8",
+    "NonZeroU16",
+    "NonZeroU32",
+    "NonZeroU64",
+    "NonZeroU128",
+    "NonZeroUsize",
+    "NonZeroI8",
+    "NonZeroI16",
+    "NonZeroI32",
+    "NonZeroI64",
+    "NonZeroI128",
+    "NonZeroIsize",
+    "char",
+    "String",
+    "bool",
+    "f32",
+    "f64",
+    "Duration",
+    "ByteSize",
+    "IpAddr",
+    "Ipv4Addr",
+    "Ipv6Addr",
+    "SocketAddr",
+    "HostPort",
+    "Url",
+];
+const OTHER_TYPES: [&str; 2] = ["PathBuf", "enum"];
+const CUSTOM_TYPE_PREFIX: &str = "custom:";
+const CUSTOM_TYPE_PATH_KEY: &str = "path";
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone)]
 pub enum AcceptedRustType {
     i8,
     i16,
     i32,
     i64,
     i128,
+    isize,
     u8,
     u16,
     u32,
     u64,
     u128,
+    usize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    char,
     String,
     bool,
     f32,
     f64,
+    /// A `std::time::Duration`, written as a sequence of amounts and units (e.g. `1h30m`).
+    Duration,
+    /// A number of bytes (`u64`), optionally with a decimal or binary unit (e.g. `64KiB`).
+    ByteSize,
+    IpAddr,
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddr,
+    /// A `host:port` pair, where the host can also be a DNS name; the generator creates a
+    /// dedicated struct, so this variant has no tokens of its own.
+    HostPort,
+    /// An absolute URL with an authority (e.g. `postgres://db:5432/app`); the generator creates a
+    /// dedicated struct, so this variant has no tokens of its own.
+    Url,
+    /// A `std::path::PathBuf`, read as it is even when it is not valid UTF-8.
+    PathBuf,
+    /// A list of values separated by the `separator` of the variable (e.g. `Vec<u16>`).
+    Vec(Box<AcceptedRustType>),
+    /// One of the `values` of the variable; the generator creates a dedicated enum for each
+    /// variable of this type, so this variant has no tokens of its own.
+    Enum,
+    /// A type of the user implementing `FromStr`, written as its path (e.g. `crate::Region`).
+    Custom(String),
 }
 
 impl AcceptedRustType {
-    pub fn to_struct_return_type(self) -> TokenStream2 {
+    pub fn to_struct_return_type(&self) -> TokenStream2 {
         let str_type = match self {
-            AcceptedRustType::i8 => "i8",
-            AcceptedRustType::i16 => "i16",
-            AcceptedRustType::i32 => "i32",
-            AcceptedRustType::i64 => "i64",
-            AcceptedRustType::i128 => "i128",
-            AcceptedRustType::u8 => "u8",
-            AcceptedRustType::u16 => "u16",
-            AcceptedRustType::u32 => "u32",
-            AcceptedRustType::u64 => "u64",
-            AcceptedRustType::u128 => "u128",
-            AcceptedRustType::f32 => "f32",
-            AcceptedRustType::f64 => "f64",
-            AcceptedRustType::bool => "bool",
-            AcceptedRustType::String => "&str",
+            AcceptedRustType::i8 => "i8".to_string(),
+            AcceptedRustType::i16 => "i16".to_string(),
+            AcceptedRustType::i32 => "i32".to_string(),
+            AcceptedRustType::i64 => "i64".to_string(),
+            AcceptedRustType::i128 => "i128".to_string(),
+            AcceptedRustType::u8 => "u8".to_string(),
+            AcceptedRustType::u16 => "u16".to_string(),
+            AcceptedRustType::u32 => "u32".to_string(),
+            AcceptedRustType::u64 => "u64".to_string(),
+            AcceptedRustType::u128 => "u128".to_string(),
+            AcceptedRustType::isize => "isize".to_string(),
+            AcceptedRustType::usize => "usize".to_string(),
+            AcceptedRustType::NonZeroU8 => "std::num::NonZeroU8".to_string(),
+            AcceptedRustType::NonZeroU16 => "std::num::NonZeroU16".to_string(),
+            AcceptedRustType::NonZeroU32 => "std::num::NonZeroU32".to_string(),
+            AcceptedRustType::NonZeroU64 => "std::num::NonZeroU64".to_string(),
+            AcceptedRustType::NonZeroU128 => "std::num::NonZeroU128".to_string(),
+            AcceptedRustType::NonZeroUsize => "std::num::NonZeroUsize".to_string(),
+            AcceptedRustType::NonZeroI8 => "std::num::NonZeroI8".to_string(),
+            AcceptedRustType::NonZeroI16 => "std::num::NonZeroI16".to_string(),
+            AcceptedRustType::NonZeroI32 => "std::num::NonZeroI32".to_string(),
+            AcceptedRustType::NonZeroI64 => "std::num::NonZeroI64".to_string(),
+            AcceptedRustType::NonZeroI128 => "std::num::NonZeroI128".to_string(),
+            AcceptedRustType::NonZeroIsize => "std::num::NonZeroIsize".to_string(),
+            AcceptedRustType::char => "char".to_string(),
+            AcceptedRustType::f32 => "f32".to_string(),
+            AcceptedRustType::f64 => "f64".to_string(),
+            AcceptedRustType::bool => "bool".to_string(),
+            AcceptedRustType::Duration => "std::time::Duration".to_string(),
+            AcceptedRustType::ByteSize => "u64".to_string(),
+            AcceptedRustType::IpAddr => "std::net::IpAddr".to_string(),
+            AcceptedRustType::Ipv4Addr => "std::net::Ipv4Addr".to_string(),
+            AcceptedRustType::Ipv6Addr => "std::net::Ipv6Addr".to_string(),
+            AcceptedRustType::SocketAddr => "std::net::SocketAddr".to_string(),
+            AcceptedRustType::String => "&str".to_string(),
+            AcceptedRustType::PathBuf => "&std::path::Path".to_string(),
+            AcceptedRustType::Custom(path) => format!("&{}", path),
+            AcceptedRustType::Vec(_)
+            | AcceptedRustType::Enum
+            | AcceptedRustType::HostPort
+            | AcceptedRustType::Url => {
+                unreachable!("the generator provides the types depending on the variable")
+            }
         };
-        TokenStream2::from_str(str_type)
+        TokenStream2::from_str(&str_type)
             .expect("AcceptedRustType::toStructReturnType always create a valid TokenStream")
     }
 
-    pub fn to_struct_self_caller(self) -> TokenStream2 {
-        let self_str = match self {
-            AcceptedRustType::String => "&self",
+    pub fn to_struct_self_caller(&self) -> TokenStream2 {
+        let self_str = if self.is_borrowed() { "&self" } else { "self" };
+        TokenStream2::from_str(self_str)
+            .expect("AcceptedRustType::toStructSelfCaller returns a valid self usage")
+    }
+
+    /// Whether the getter returns a reference to the field, instead of a copy.
+    pub fn is_borrowed(&self) -> bool {
+        match self {
+            AcceptedRustType::String
+            | AcceptedRustType::PathBuf
+            | AcceptedRustType::Vec(_)
+            | AcceptedRustType::HostPort
+            | AcceptedRustType::Url
+            | AcceptedRustType::Custom(_) => true,
             AcceptedRustType::i8
             | AcceptedRustType::i16
             | AcceptedRustType::i32
@@ -58,45 +187,360 @@ impl AcceptedRustType {
             | AcceptedRustType::u32
             | AcceptedRustType::u64
             | AcceptedRustType::u128
+            | AcceptedRustType::isize
+            | AcceptedRustType::usize
+            | AcceptedRustType::NonZeroU8
+            | AcceptedRustType::NonZeroU16
+            | AcceptedRustType::NonZeroU32
+            | AcceptedRustType::NonZeroU64
+            | AcceptedRustType::NonZeroU128
+            | AcceptedRustType::NonZeroUsize
+            | AcceptedRustType::NonZeroI8
+            | AcceptedRustType::NonZeroI16
+            | AcceptedRustType::NonZeroI32
+            | AcceptedRustType::NonZeroI64
+            | AcceptedRustType::NonZeroI128
+            | AcceptedRustType::NonZeroIsize
+            | AcceptedRustType::char
             | AcceptedRustType::f32
             | AcceptedRustType::f64
-            | AcceptedRustType::bool => "self",
+            | AcceptedRustType::bool
+            | AcceptedRustType::Duration
+            | AcceptedRustType::ByteSize
+            | AcceptedRustType::IpAddr
+            | AcceptedRustType::Ipv4Addr
+            | AcceptedRustType::Ipv6Addr
+            | AcceptedRustType::SocketAddr
+            | AcceptedRustType::Enum => false,
+        }
+    }
+
+    /// The type of the elements of a list, or the type itself.
+    pub fn element_type(&self) -> &AcceptedRustType {
+        match self {
+            AcceptedRustType::Vec(element) => element,
+            _ => self,
+        }
+    }
+
+    /// The function parsing the values of this type, when it does not implement `FromStr`.
+    pub fn runtime_helper(&self) -> Option<RuntimeHelper> {
+        match self.element_type() {
+            AcceptedRustType::Duration => Some(RuntimeHelper::Duration),
+            AcceptedRustType::ByteSize => Some(RuntimeHelper::ByteSize),
+            _ => None,
+        }
+    }
+
+    /// The error message of an invalid value, replacing the one of `FromStr`.
+    pub fn expected_format(&self) -> Option<&'static str> {
+        match self.element_type() {
+            AcceptedRustType::IpAddr => {
+                Some("expected an IPv4 or an IPv6 address (e.g. '127.0.0.1' or '::1')")
+            }
+            AcceptedRustType::Ipv4Addr => Some("expected an IPv4 address (e.g. '127.0.0.1')"),
+            AcceptedRustType::Ipv6Addr => Some("expected an IPv6 address (e.g. '::1')"),
+            AcceptedRustType::SocketAddr => {
+                Some("expected an IP address and a port (e.g. '0.0.0.0:8080' or '[::1]:8080')")
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks that `value` is a valid value of this type, with the same rules of the generated
+    /// code; the elements of lists and the values of enums are checked by the caller.
+    pub fn check_value(&self, value: &str) -> Result<(), String> {
+        let result = match self {
+            AcceptedRustType::i8 => check_parse::<i8>(value),
+            AcceptedRustType::i16 => check_parse::<i16>(value),
+            AcceptedRustType::i32 => check_parse::<i32>(value),
+            AcceptedRustType::i64 => check_parse::<i64>(value),
+            AcceptedRustType::i128 => check_parse::<i128>(value),
+            AcceptedRustType::u8 => check_parse::<u8>(value),
+            AcceptedRustType::u16 => check_parse::<u16>(value),
+            AcceptedRustType::u32 => check_parse::<u32>(value),
+            AcceptedRustType::u64 => check_parse::<u64>(value),
+            AcceptedRustType::u128 => check_parse::<u128>(value),
+            AcceptedRustType::isize => check_parse::<isize>(value),
+            AcceptedRustType::usize => check_parse::<usize>(value),
+            AcceptedRustType::NonZeroU8 => check_parse::<std::num::NonZeroU8>(value),
+            AcceptedRustType::NonZeroU16 => check_parse::<std::num::NonZeroU16>(value),
+            AcceptedRustType::NonZeroU32 => check_parse::<std::num::NonZeroU32>(value),
+            AcceptedRustType::NonZeroU64 => check_parse::<std::num::NonZeroU64>(value),
+            AcceptedRustType::NonZeroU128 => check_parse::<std::num::NonZeroU128>(value),
+            AcceptedRustType::NonZeroUsize => check_parse::<std::num::NonZeroUsize>(value),
+            AcceptedRustType::NonZeroI8 => check_parse::<std::num::NonZeroI8>(value),
+            AcceptedRustType::NonZeroI16 => check_parse::<std::num::NonZeroI16>(value),
+            AcceptedRustType::NonZeroI32 => check_parse::<std::num::NonZeroI32>(value),
+            AcceptedRustType::NonZeroI64 => check_parse::<std::num::NonZeroI64>(value),
+            AcceptedRustType::NonZeroI128 => check_parse::<std::num::NonZeroI128>(value),
+            AcceptedRustType::NonZeroIsize => check_parse::<std::num::NonZeroIsize>(value),
+            AcceptedRustType::char => check_parse::<char>(value),
+            AcceptedRustType::f32 => check_parse::<f32>(value),
+            AcceptedRustType::f64 => check_parse::<f64>(value),
+            AcceptedRustType::bool => check_parse::<bool>(value),
+            AcceptedRustType::String
+            | AcceptedRustType::PathBuf
+            | AcceptedRustType::Vec(_)
+            | AcceptedRustType::Enum => Ok(()),
+            // The type is only known by the compiler, the value is parsed when loading the env.
+            AcceptedRustType::Custom(_) => Ok(()),
+            AcceptedRustType::Duration => RuntimeHelper::Duration.check(value),
+            AcceptedRustType::ByteSize => RuntimeHelper::ByteSize.check(value),
+            AcceptedRustType::IpAddr => check_parse::<std::net::IpAddr>(value),
+            AcceptedRustType::Ipv4Addr => check_parse::<std::net::Ipv4Addr>(value),
+            AcceptedRustType::Ipv6Addr => check_parse::<std::net::Ipv6Addr>(value),
+            AcceptedRustType::SocketAddr => check_parse::<std::net::SocketAddr>(value),
+            AcceptedRustType::HostPort => RuntimeHelper::HostPort.check(value),
+            AcceptedRustType::Url => RuntimeHelper::Url.check(value),
         };
-        TokenStream2::from_str(self_str)
-            .expect("AcceptedRustType::toStructSelfCaller returns a valid self usage")
+        match self.expected_format() {
+            Some(expected_format) => result.map_err(|_| expected_format.to_string()),
+            None => result,
+        }
     }
 }
 
+fn check_parse<T>(value: &str) -> Result<(), String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse::<T>().map(drop).map_err(|it| it.to_string())
+}
+
 impl Display for AcceptedRustType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                AcceptedRustType::i8 => "i8",
-                AcceptedRustType::i16 => "i16",
-                AcceptedRustType::i32 => "i32",
-                AcceptedRustType::i64 => "i64",
-                AcceptedRustType::i128 => "i128",
-                AcceptedRustType::u8 => "u8",
-                AcceptedRustType::u16 => "u16",
-                AcceptedRustType::u32 => "u32",
-                AcceptedRustType::u64 => "u64",
-                AcceptedRustType::u128 => "u128",
-                AcceptedRustType::f32 => "f32",
-                AcceptedRustType::f64 => "f64",
-                AcceptedRustType::String => "String",
-                AcceptedRustType::bool => "bool",
+        match self {
+            AcceptedRustType::i8 => write!(f, "i8"),
+            AcceptedRustType::i16 => write!(f, "i16"),
+            AcceptedRustType::i32 => write!(f, "i32"),
+            AcceptedRustType::i64 => write!(f, "i64"),
+            AcceptedRustType::i128 => write!(f, "i128"),
+            AcceptedRustType::u8 => write!(f, "u8"),
+            AcceptedRustType::u16 => write!(f, "u16"),
+            AcceptedRustType::u32 => write!(f, "u32"),
+            AcceptedRustType::u64 => write!(f, "u64"),
+            AcceptedRustType::u128 => write!(f, "u128"),
+            AcceptedRustType::isize => write!(f, "isize"),
+            AcceptedRustType::usize => write!(f, "usize"),
+            AcceptedRustType::NonZeroU8 => write!(f, "NonZeroU8"),
+            AcceptedRustType::NonZeroU16 => write!(f, "NonZeroU16"),
+            AcceptedRustType::NonZeroU32 => write!(f, "NonZeroU32"),
+            AcceptedRustType::NonZeroU64 => write!(f, "NonZeroU64"),
+            AcceptedRustType::NonZeroU128 => write!(f, "NonZeroU128"),
+            AcceptedRustType::NonZeroUsize => write!(f, "NonZeroUsize"),
+            AcceptedRustType::NonZeroI8 => write!(f, "NonZeroI8"),
+            AcceptedRustType::NonZeroI16 => write!(f, "NonZeroI16"),
+            AcceptedRustType::NonZeroI32 => write!(f, "NonZeroI32"),
+            AcceptedRustType::NonZeroI64 => write!(f, "NonZeroI64"),
+            AcceptedRustType::NonZeroI128 => write!(f, "NonZeroI128"),
+            AcceptedRustType::NonZeroIsize => write!(f, "NonZeroIsize"),
+            AcceptedRustType::char => write!(f, "char"),
+            AcceptedRustType::f32 => write!(f, "f32"),
+            AcceptedRustType::f64 => write!(f, "f64"),
+            AcceptedRustType::String => write!(f, "String"),
+            AcceptedRustType::bool => write!(f, "bool"),
+            AcceptedRustType::Duration => write!(f, "Duration"),
+            AcceptedRustType::ByteSize => write!(f, "ByteSize"),
+            AcceptedRustType::IpAddr => write!(f, "IpAddr"),
+            AcceptedRustType::Ipv4Addr => write!(f, "Ipv4Addr"),
+            AcceptedRustType::Ipv6Addr => write!(f, "Ipv6Addr"),
+            AcceptedRustType::SocketAddr => write!(f, "SocketAddr"),
+            AcceptedRustType::HostPort => write!(f, "HostPort"),
+            AcceptedRustType::Url => write!(f, "Url"),
+            AcceptedRustType::PathBuf => write!(f, "PathBuf"),
+            AcceptedRustType::Vec(element) => write!(f, "Vec<{}>", element),
+            AcceptedRustType::Enum => write!(f, "enum"),
+            AcceptedRustType::Custom(path) => write!(f, "{}{}", CUSTOM_TYPE_PREFIX, path),
+        }
+    }
+}
+
+impl FromStr for AcceptedRustType {
+    type Err = AcceptedRustTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unsupported_type = || {
+            UnsupportedTypeError {
+                rust_type: s.to_string(),
+            }
+            .into()
+        };
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix(CUSTOM_TYPE_PREFIX) {
+            return AcceptedRustType::custom(path);
+        }
+        if let Some(element) = s.strip_prefix("Vec<").and_then(|it| it.strip_suffix('>')) {
+            return match element.parse::<AcceptedRustType>()? {
+                AcceptedRustType::Vec(_) | AcceptedRustType::Enum | AcceptedRustType::PathBuf => {
+                    Err(unsupported_type())
+                }
+                element => Ok(AcceptedRustType::Vec(Box::new(element))),
+            };
+        }
+        match s {
+            "i8" => Ok(AcceptedRustType::i8),
+            "i16" => Ok(AcceptedRustType::i16),
+            "i32" => Ok(AcceptedRustType::i32),
+            "i64" => Ok(AcceptedRustType::i64),
+            "i128" => Ok(AcceptedRustType::i128),
+            "u8" => Ok(AcceptedRustType::u8),
+            "u16" => Ok(AcceptedRustType::u16),
+            "u32" => Ok(AcceptedRustType::u32),
+            "u64" => Ok(AcceptedRustType::u64),
+            "u128" => Ok(AcceptedRustType::u128),
+            "isize" => Ok(AcceptedRustType::isize),
+            "usize" => Ok(AcceptedRustType::usize),
+            "NonZeroU8" => Ok(AcceptedRustType::NonZeroU8),
+            "NonZeroU16" => Ok(AcceptedRustType::NonZeroU16),
+            "NonZeroU32" => Ok(AcceptedRustType::NonZeroU32),
+            "NonZeroU64" => Ok(AcceptedRustType::NonZeroU64),
+            "NonZeroU128" => Ok(AcceptedRustType::NonZeroU128),
+            "NonZeroUsize" => Ok(AcceptedRustType::NonZeroUsize),
+            "NonZeroI8" => Ok(AcceptedRustType::NonZeroI8),
+            "NonZeroI16" => Ok(AcceptedRustType::NonZeroI16),
+            "NonZeroI32" => Ok(AcceptedRustType::NonZeroI32),
+            "NonZeroI64" => Ok(AcceptedRustType::NonZeroI64),
+            "NonZeroI128" => Ok(AcceptedRustType::NonZeroI128),
+            "NonZeroIsize" => Ok(AcceptedRustType::NonZeroIsize),
+            "char" => Ok(AcceptedRustType::char),
+            "f32" => Ok(AcceptedRustType::f32),
+            "f64" => Ok(AcceptedRustType::f64),
+            "String" => Ok(AcceptedRustType::String),
+            "bool" => Ok(AcceptedRustType::bool),
+            "Duration" => Ok(AcceptedRustType::Duration),
+            "ByteSize" => Ok(AcceptedRustType::ByteSize),
+            "IpAddr" => Ok(AcceptedRustType::IpAddr),
+            "Ipv4Addr" => Ok(AcceptedRustType::Ipv4Addr),
+            "Ipv6Addr" => Ok(AcceptedRustType::Ipv6Addr),
+            "SocketAddr" => Ok(AcceptedRustType::SocketAddr),
+            "HostPort" => Ok(AcceptedRustType::HostPort),
+            "Url" => Ok(AcceptedRustType::Url),
+            "PathBuf" => Ok(AcceptedRustType::PathBuf),
+            "enum" => Ok(AcceptedRustType::Enum),
+            _ => Err(unsupported_type()),
+        }
+    }
+}
+
+impl AcceptedRustType {
+    /// A type of the user, checked to be a valid type path; whether it implements `FromStr` is
+    /// only known when the generated code is compiled.
+    pub fn custom(path: &str) -> Result<Self, AcceptedRustTypeError> {
+        let path = path.trim();
+        match syn::parse_str::<syn::Path>(path) {
+            Ok(_) => Ok(AcceptedRustType::Custom(path.to_string())),
+            Err(_) => Err(InvalidTypePathError {
+                path: path.to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+error_node! {
+    pub type AcceptedRustTypeError<UnsupportedTypeError, InvalidTypePathError> = "invalid type"
+}
+
+#[error_leaf(format!(
+    "unsupported type '{}', expected one of {}, a list of them (e.g. 'Vec<u16>'), one of {} or a type implementing FromStr (e.g. '{}crate::Region')",
+    self.rust_type,
+    SCALAR_TYPES.join(", "),
+    OTHER_TYPES.join(", "),
+    CUSTOM_TYPE_PREFIX,
+))]
+pub struct UnsupportedTypeError {
+    rust_type: String,
+}
+
+#[error_leaf(format!(
+    "invalid type path '{}', expected the path of a type implementing FromStr (e.g. 'crate::Region')",
+    self.path,
+))]
+pub struct InvalidTypePathError {
+    path: String,
+}
+
+impl<'de> Deserialize<'de> for AcceptedRustType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AcceptedRustTypeVisitor {})
+    }
+}
+
+struct AcceptedRustTypeVisitor {}
+
+impl<'de> Visitor<'de> for AcceptedRustTypeVisitor {
+    type Value = AcceptedRustType;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a type name or a map with the path of a type")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+
+    // A custom type can also be written as `{ path: "crate::Region" }`.
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut path: Option<String> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key != CUSTOM_TYPE_PATH_KEY {
+                return Err(A::Error::unknown_field(&key, &[CUSTOM_TYPE_PATH_KEY]));
             }
-        )
+            path = Some(map.next_value()?);
+        }
+        let path = path.ok_or_else(|| A::Error::missing_field(CUSTOM_TYPE_PATH_KEY))?;
+        AcceptedRustType::custom(&path).map_err(A::Error::custom)
     }
 }
 
 impl ToTokens for AcceptedRustType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        tokens.extend(
-            TokenStream2::from_str(&self.to_string())
-                .expect("AcceptedRustType contains valid Rust types"),
-        );
+        match self {
+            AcceptedRustType::Duration => tokens.extend(quote! { std::time::Duration }),
+            AcceptedRustType::ByteSize => tokens.extend(quote! { u64 }),
+            AcceptedRustType::PathBuf => tokens.extend(quote! { std::path::PathBuf }),
+            AcceptedRustType::IpAddr
+            | AcceptedRustType::Ipv4Addr
+            | AcceptedRustType::Ipv6Addr
+            | AcceptedRustType::SocketAddr => {
+                let type_name = TokenStream2::from_str(&self.to_string())
+                    .expect("AcceptedRustType contains valid Rust types");
+                tokens.extend(quote! { std::net::#type_name })
+            }
+            AcceptedRustType::NonZeroU8
+            | AcceptedRustType::NonZeroU16
+            | AcceptedRustType::NonZeroU32
+            | AcceptedRustType::NonZeroU64
+            | AcceptedRustType::NonZeroU128
+            | AcceptedRustType::NonZeroUsize
+            | AcceptedRustType::NonZeroI8
+            | AcceptedRustType::NonZeroI16
+            | AcceptedRustType::NonZeroI32
+            | AcceptedRustType::NonZeroI64
+            | AcceptedRustType::NonZeroI128
+            | AcceptedRustType::NonZeroIsize => {
+                let type_name = TokenStream2::from_str(&self.to_string())
+                    .expect("AcceptedRustType contains valid Rust types");
+                tokens.extend(quote! { std::num::#type_name })
+            }
+            AcceptedRustType::Vec(element) => tokens.extend(quote! { Vec<#element> }),
+            AcceptedRustType::Custom(path) => tokens
+                .extend(TokenStream2::from_str(path).expect("custom types are valid Rust paths")),
+            _ => tokens.extend(
+                TokenStream2::from_str(&self.to_string())
+                    .expect("AcceptedRustType contains valid Rust types"),
+            ),
+        }
     }
 }
@@ -1,35 +1,63 @@
+use std::path::PathBuf;
+
 use proc_macro::TokenStream;
 use proc_macro2::{Span as Span2, TokenStream as TokenStream2};
 use quote::{format_ident, quote, ToTokens};
 use syn::token::Struct as SynStruct;
 use syn::Ident as SynIdent;
 
-use crate::{EmptyStruct, EnvVariableDeclarations};
+use crate::{
+    accepted_rust_type::AcceptedRustType, naming::to_pascal_case, runtime_helpers::RuntimeHelper,
+    variable_declarations::EnvVariableSpec, EmptyStruct, EnvVariableDeclarations,
+};
 
 pub struct DeclarativeEnvGenerator {
     empty_struct: EmptyStruct,
     variable_declarations: EnvVariableDeclarations,
+    definition_files: Vec<PathBuf>,
 }
 
 impl DeclarativeEnvGenerator {
     pub fn new(
         empty_struct: EmptyStruct,
         variable_declarations: EnvVariableDeclarations,
+        definition_files: Vec<PathBuf>,
     ) -> DeclarativeEnvGenerator {
         DeclarativeEnvGenerator {
             empty_struct,
             variable_declarations,
+            definition_files,
         }
     }
 
     pub fn generate(&self) -> TokenStream {
         let mut result = TokenStream2::new();
         result.extend(self.create_struct_def());
+        result.extend(self.custom_types_code());
         result.extend(self.create_struct_impl());
+        result.extend(self.enums_code());
+        result.extend(self.hostport_code());
+        result.extend(self.url_code());
+        result.extend(self.secret_code());
         result.extend(self.requiredenvnotfounderror_code());
+        result.extend(self.invalidenvvalueerror_code());
+        result.extend(self.envfileerror_code());
+        result.extend(self.definition_files_tracking());
         result.into()
     }
 
+    // Cargo is not aware of the files read by the macro, so each of them is included in the
+    // generated code: editing a definition file then triggers the recompilation of the crate.
+    fn definition_files_tracking(&self) -> TokenStream2 {
+        let paths = self
+            .definition_files
+            .iter()
+            .map(|it| it.display().to_string());
+        quote! {
+            #(const _: &[u8] = include_bytes!(#paths);)*
+        }
+    }
+
     fn create_struct_def(&self) -> TokenStream2 {
         let mut token_stream: TokenStream2 = TokenStream2::new();
         token_stream.extend(quote! { #[derive(Debug, Clone)] });
@@ -42,8 +70,12 @@ impl DeclarativeEnvGenerator {
         let mut fields = TokenStream2::new();
         for it in self.variable_declarations.as_ref() {
             let variable_name = SynIdent::new(it.name(), Span2::call_site());
-            let variable_type = it.rust_type();
-            fields.extend(quote! { #variable_name: #variable_type, });
+            let variable_type = self.value_type(it);
+            if it.is_required() {
+                fields.extend(quote! { #variable_name: #variable_type, });
+            } else {
+                fields.extend(quote! { #variable_name: Option<#variable_type>, });
+            }
         }
         token_stream.extend(quote! { { #fields } });
         token_stream
@@ -53,47 +85,132 @@ impl DeclarativeEnvGenerator {
         let mut token_stream: TokenStream2 = TokenStream2::new();
         let struct_name = SynIdent::new(self.empty_struct.struct_name(), Span2::call_site());
         let requiredenvnotfounderror_struct_ident = self.requiredenvnotfounderror_struct_ident();
+        let invalidenvvalueerror_struct_ident = self.invalidenvvalueerror_struct_ident();
         let mut functions: TokenStream2 = TokenStream2::new();
         let mut variable_reads = TokenStream2::new();
         for it in self.variable_declarations.as_ref() {
             let var_name = SynIdent::new(it.name(), Span2::call_site());
             let var_name_str = it.name();
-            let var_type = it.rust_type();
-            let default_behaviour = match &it.default_value() {
-                Some(v) => {
-                    quote! {
-                        #v.parse::<#var_type>()?
+            // The errors of a value read from a file name the file as well.
+            let key = if it.file_fallback() {
+                quote! { &key }
+            } else {
+                quote! { #var_name_str }
+            };
+            let parsed_value = self.parsed_value(it, quote! { v }, key);
+            // Paths are read as they are, even when they are not valid UTF-8.
+            let read_variable = match it.rust_type() {
+                AcceptedRustType::PathBuf => quote! {
+                    std::env::var_os(#var_name_str).ok_or(std::env::VarError::NotPresent)
+                },
+                _ => quote! { std::env::var(#var_name_str) },
+            };
+            // A value which is not valid unicode is shown by `VarError`, so the errors of secrets
+            // and URLs are built here, without the secret or the credentials.
+            let other_error = if it.is_secret() {
+                quote! {
+                    Err(std::env::VarError::NotUnicode(_)) => {
+                        return Err(#invalidenvvalueerror_struct_ident::new(
+                            #var_name_str,
+                            "[REDACTED]",
+                            String::from("the value is not valid unicode"),
+                        )
+                        .into())
+                    }
+                }
+            } else if matches!(it.rust_type().element_type(), AcceptedRustType::Url) {
+                let url_struct_ident = self.url_struct_ident();
+                quote! {
+                    Err(std::env::VarError::NotUnicode(v)) => {
+                        return Err(#invalidenvvalueerror_struct_ident::new(
+                            #var_name_str,
+                            &#url_struct_ident::redact_credentials(&v.to_string_lossy()),
+                            String::from("the value is not valid unicode"),
+                        )
+                        .into())
                     }
                 }
+            } else {
+                quote! { Err(e) => return Err(e.into()), }
+            };
+            let default_behaviour = match it.default_value() {
+                Some(v) => self.parsed_value(it, quote! { #v }, quote! { #var_name_str }),
                 None => quote! {
                     return Err(#requiredenvnotfounderror_struct_ident::new(#var_name_str).into())
                 },
             };
-            variable_reads.extend(quote! {
-                let #var_name = match std::env::var(#var_name_str) {
-                    Ok(v) => v.parse::<#var_type>()?,
+            let read_arms = if it.is_required() {
+                quote! {
+                    Ok(v) => #parsed_value,
                     Err(std::env::VarError::NotPresent) => #default_behaviour,
-                    Err(e) => return Err(e.into()),
-                };
-            });
+                    #other_error
+                }
+            } else {
+                quote! {
+                    Ok(v) => Some(#parsed_value),
+                    Err(std::env::VarError::NotPresent) => None,
+                    #other_error
+                }
+            };
+            if it.file_fallback() {
+                let read_file = self.read_file_fallback(it, read_variable);
+                variable_reads.extend(quote! {
+                    let #var_name = {
+                        #read_file
+                        match read {
+                            #read_arms
+                        }
+                    };
+                });
+            } else {
+                variable_reads.extend(quote! {
+                    let #var_name = match #read_variable {
+                        #read_arms
+                    };
+                });
+            }
         }
         let all_vars = self
             .variable_declarations
             .iter()
             .map(|it| SynIdent::new(it.name(), Span2::call_site()));
+        let runtime_helpers = self.runtime_helpers_code();
         functions.extend(quote! {
             pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+                #runtime_helpers
                 #variable_reads
                 Ok(#struct_name { #(#all_vars),* })
             }
         });
         for it in self.variable_declarations.as_ref() {
             let fn_name = SynIdent::new(it.name(), Span2::call_site());
-            let fn_return_type = it.rust_type().to_struct_return_type();
+            let fn_return_type = self.return_type(it);
             let self_ref = it.rust_type().to_struct_self_caller();
+            let (fn_return_type, fn_body) = if it.is_required() {
+                (fn_return_type, quote! { #self_ref.#fn_name })
+            } else if it.is_secret()
+                || matches!(
+                    it.rust_type(),
+                    AcceptedRustType::HostPort
+                        | AcceptedRustType::Url
+                        | AcceptedRustType::Custom(_)
+                )
+            {
+                (
+                    quote! { Option<#fn_return_type> },
+                    quote! { self.#fn_name.as_ref() },
+                )
+            } else if it.rust_type().is_borrowed() {
+                (
+                    quote! { Option<#fn_return_type> },
+                    quote! { self.#fn_name.as_deref() },
+                )
+            } else {
+                (quote! { Option<#fn_return_type> }, quote! { self.#fn_name })
+            };
             functions.extend(quote! {
                 pub fn #fn_name(&self) -> #fn_return_type {
-                    #self_ref.#fn_name
+                    #fn_body
                 }
             });
         }
@@ -105,6 +222,609 @@ impl DeclarativeEnvGenerator {
         token_stream
     }
 
+    // The type of the field of the variable.
+    fn value_type(&self, spec: &EnvVariableSpec) -> TokenStream2 {
+        if spec.is_secret() {
+            return self.secret_struct_ident().to_token_stream();
+        }
+        self.type_tokens(spec, spec.rust_type())
+    }
+
+    // The type returned by the getter of the variable.
+    fn return_type(&self, spec: &EnvVariableSpec) -> TokenStream2 {
+        if spec.is_secret() {
+            let secret_struct_ident = self.secret_struct_ident();
+            return quote! { &#secret_struct_ident };
+        }
+        match spec.rust_type() {
+            AcceptedRustType::Enum => self.enum_ident(spec).to_token_stream(),
+            AcceptedRustType::HostPort => {
+                let hostport_struct_ident = self.hostport_struct_ident();
+                quote! { &#hostport_struct_ident }
+            }
+            AcceptedRustType::Url => {
+                let url_struct_ident = self.url_struct_ident();
+                quote! { &#url_struct_ident }
+            }
+            AcceptedRustType::Vec(element_type) => {
+                let element_type = self.type_tokens(spec, element_type);
+                quote! { &[#element_type] }
+            }
+            rust_type => rust_type.to_struct_return_type(),
+        }
+    }
+
+    // The tokens of `rust_type`, which can be a type generated for the variable.
+    fn type_tokens(&self, spec: &EnvVariableSpec, rust_type: &AcceptedRustType) -> TokenStream2 {
+        match rust_type {
+            AcceptedRustType::Enum => self.enum_ident(spec).to_token_stream(),
+            AcceptedRustType::HostPort => self.hostport_struct_ident().to_token_stream(),
+            AcceptedRustType::Url => self.url_struct_ident().to_token_stream(),
+            AcceptedRustType::Vec(element_type) => {
+                let element_type = self.type_tokens(spec, element_type);
+                quote! { Vec<#element_type> }
+            }
+            rust_type => rust_type.to_token_stream(),
+        }
+    }
+
+    fn enum_ident(&self, spec: &EnvVariableSpec) -> SynIdent {
+        format_ident!(
+            "{}{}",
+            self.empty_struct.struct_name(),
+            to_pascal_case(spec.name())
+        )
+    }
+
+    // Each enum variable has a dedicated enum, with a variant for each of its values.
+    fn enums_code(&self) -> TokenStream2 {
+        let mut token_stream = TokenStream2::new();
+        let visibility = self.empty_struct.visibility();
+        for it in self.variable_declarations.iter() {
+            if !matches!(it.rust_type(), AcceptedRustType::Enum) {
+                continue;
+            }
+            let enum_name = self.enum_ident(it);
+            let values = it.values();
+            let variants: Vec<SynIdent> = values
+                .iter()
+                .map(|value| SynIdent::new(&to_pascal_case(value), Span2::call_site()))
+                .collect();
+            let matches = values.iter().map(|value| {
+                if it.is_case_insensitive() {
+                    quote! { s.eq_ignore_ascii_case(#value) }
+                } else {
+                    quote! { s == #value }
+                }
+            });
+            let expected_values = it.expected_values_message();
+            token_stream.extend(quote! {
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                #visibility enum #enum_name {
+                    #(#variants),*
+                }
+
+                impl std::str::FromStr for #enum_name {
+                    type Err = String;
+
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        #(
+                            if #matches {
+                                return Ok(Self::#variants);
+                            }
+                        )*
+                        Err(String::from(#expected_values))
+                    }
+                }
+
+                impl std::fmt::Display for #enum_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            #(Self::#variants => f.write_str(#values),)*
+                        }
+                    }
+                }
+            });
+        }
+        token_stream
+    }
+
+    fn hostport_struct_ident(&self) -> SynIdent {
+        format_ident!("{}HostPort", self.empty_struct.struct_name())
+    }
+
+    // The struct of the `HostPort` variables, generated only when one of them is declared.
+    fn hostport_code(&self) -> TokenStream2 {
+        let has_hostport = self
+            .variable_declarations
+            .iter()
+            .any(|it| matches!(it.rust_type().element_type(), AcceptedRustType::HostPort));
+        if !has_hostport {
+            return TokenStream2::new();
+        }
+        let struct_name = self.hostport_struct_ident();
+        let visibility = self.empty_struct.visibility();
+        let parse_host_port = RuntimeHelper::HostPort.to_token_stream();
+        quote! {
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            #visibility struct #struct_name {
+                host: String,
+                port: u16,
+            }
+
+            impl #struct_name {
+                pub fn host(&self) -> &str {
+                    &self.host
+                }
+
+                pub fn port(&self) -> u16 {
+                    self.port
+                }
+            }
+
+            impl std::str::FromStr for #struct_name {
+                type Err = String;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    #parse_host_port
+                    parse_host_port(s).map(|(host, port)| Self { host, port })
+                }
+            }
+
+            impl std::fmt::Display for #struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    if self.host.contains(':') {
+                        write!(f, "[{}]:{}", self.host, self.port)
+                    } else {
+                        write!(f, "{}:{}", self.host, self.port)
+                    }
+                }
+            }
+        }
+    }
+
+    fn url_struct_ident(&self) -> SynIdent {
+        format_ident!("{}Url", self.empty_struct.struct_name())
+    }
+
+    // The struct of the `Url` variables, generated only when one of them is declared; its `Debug`
+    // and `Display` implementations do not show the credentials.
+    fn url_code(&self) -> TokenStream2 {
+        let has_url = self
+            .variable_declarations
+            .iter()
+            .any(|it| matches!(it.rust_type().element_type(), AcceptedRustType::Url));
+        if !has_url {
+            return TokenStream2::new();
+        }
+        let struct_name = self.url_struct_ident();
+        let struct_name_str = struct_name.to_string();
+        let visibility = self.empty_struct.visibility();
+        let url_helper = RuntimeHelper::Url.to_token_stream();
+        quote! {
+            #[derive(Clone, PartialEq, Eq, Hash)]
+            #visibility struct #struct_name {
+                url: String,
+                redacted: String,
+                scheme: String,
+                username: String,
+                password: Option<String>,
+                host: String,
+                port: Option<u16>,
+                path: String,
+                query: Option<String>,
+                fragment: Option<String>,
+            }
+
+            impl #struct_name {
+                /// The whole URL, credentials included.
+                pub fn as_str(&self) -> &str {
+                    &self.url
+                }
+
+                pub fn scheme(&self) -> &str {
+                    &self.scheme
+                }
+
+                /// The user name, as written in the URL (e.g. `%40` is not decoded to `@`).
+                pub fn username(&self) -> &str {
+                    &self.username
+                }
+
+                /// The password, as written in the URL (e.g. `%40` is not decoded to `@`).
+                pub fn password(&self) -> Option<&str> {
+                    self.password.as_deref()
+                }
+
+                pub fn host(&self) -> &str {
+                    &self.host
+                }
+
+                pub fn port(&self) -> Option<u16> {
+                    self.port
+                }
+
+                pub fn path(&self) -> &str {
+                    &self.path
+                }
+
+                pub fn query(&self) -> Option<&str> {
+                    self.query.as_deref()
+                }
+
+                pub fn fragment(&self) -> Option<&str> {
+                    self.fragment.as_deref()
+                }
+
+                fn redact_credentials(value: &str) -> String {
+                    #url_helper
+                    redact_url_credentials(value)
+                }
+            }
+
+            impl std::str::FromStr for #struct_name {
+                type Err = String;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    #url_helper
+                    parse_url(s).map(|it| Self {
+                        url: s.to_string(),
+                        redacted: it.redacted,
+                        scheme: it.scheme,
+                        username: it.username,
+                        password: it.password,
+                        host: it.host,
+                        port: it.port,
+                        path: it.path,
+                        query: it.query,
+                        fragment: it.fragment,
+                    })
+                }
+            }
+
+            impl std::fmt::Debug for #struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_tuple(#struct_name_str).field(&self.redacted).finish()
+                }
+            }
+
+            impl std::fmt::Display for #struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&self.redacted)
+                }
+            }
+        }
+    }
+
+    fn secret_struct_ident(&self) -> SynIdent {
+        format_ident!("{}Secret", self.empty_struct.struct_name())
+    }
+
+    // The struct of the secret variables, generated only when one of them is declared: the value
+    // is only available with `expose`, and it is overwritten with zeros when dropped.
+    fn secret_code(&self) -> TokenStream2 {
+        if !self.variable_declarations.iter().any(|it| it.is_secret()) {
+            return TokenStream2::new();
+        }
+        let struct_name = self.secret_struct_ident();
+        let visibility = self.empty_struct.visibility();
+        quote! {
+            #[derive(Clone)]
+            #visibility struct #struct_name {
+                value: String,
+            }
+
+            impl #struct_name {
+                /// The value of the secret, which should not be logged.
+                pub fn expose(&self) -> &str {
+                    &self.value
+                }
+            }
+
+            impl std::fmt::Debug for #struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("[REDACTED]")
+                }
+            }
+
+            impl std::fmt::Display for #struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("[REDACTED]")
+                }
+            }
+
+            impl Drop for #struct_name {
+                fn drop(&mut self) {
+                    let mut bytes = std::mem::take(&mut self.value).into_bytes();
+                    let capacity = bytes.capacity();
+                    let pointer = bytes.as_mut_ptr();
+                    for offset in 0..capacity {
+                        // SAFETY: the offset is inside the allocation of `bytes`, and the volatile
+                        // writes are not removed by the compiler as dead stores.
+                        unsafe { std::ptr::write_volatile(pointer.add(offset), 0) };
+                    }
+                    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+                }
+            }
+        }
+    }
+
+    // Reads the variable into `read` as `read_variable` would, unless the variable `<NAME>_FILE`
+    // is set: the value is then the content of the file it points to, without the trailing newline,
+    // and `key` names the file for the errors.
+    fn read_file_fallback(
+        &self,
+        spec: &EnvVariableSpec,
+        read_variable: TokenStream2,
+    ) -> TokenStream2 {
+        let envfileerror_struct_ident = self.envfileerror_struct_ident();
+        let var_name_str = spec.name();
+        let file_var_name_str = format!("{}_FILE", spec.name());
+        let conflict_message = format!(
+            "{} is set as well, remove either {} or {}",
+            var_name_str, var_name_str, file_var_name_str
+        );
+        let file_value = match spec.rust_type() {
+            AcceptedRustType::PathBuf => quote! { std::ffi::OsString::from(content) },
+            _ => quote! { content },
+        };
+        quote! {
+            let (read, key) = match (
+                std::env::var_os(#var_name_str),
+                std::env::var_os(#file_var_name_str),
+            ) {
+                (Some(_), Some(path)) => {
+                    return Err(#envfileerror_struct_ident::new(
+                        #var_name_str,
+                        &path,
+                        String::from(#conflict_message),
+                    )
+                    .into())
+                }
+                (None, Some(path)) => {
+                    let mut content = std::fs::read_to_string(&path).map_err(|it| {
+                        #envfileerror_struct_ident::new(#var_name_str, &path, it.to_string())
+                    })?;
+                    if content.ends_with('\n') {
+                        content.pop();
+                        if content.ends_with('\r') {
+                            content.pop();
+                        }
+                    }
+                    let key = format!(
+                        "{} (read from '{}')",
+                        #var_name_str,
+                        std::path::Path::new(&path).display()
+                    );
+                    (Ok(#file_value), key)
+                }
+                _ => (#read_variable, String::from(#var_name_str)),
+            };
+        }
+    }
+
+    // The custom types are only required to implement `FromStr`, so a missing implementation is
+    // reported with the name of the variable instead of the errors of the parsing code.
+    fn custom_types_code(&self) -> TokenStream2 {
+        let mut token_stream = TokenStream2::new();
+        for it in self.variable_declarations.iter() {
+            let (AcceptedRustType::Custom(_), None) = (it.rust_type().element_type(), it.parser())
+            else {
+                continue;
+            };
+            let custom_type = it.rust_type().element_type();
+            let message = format!(
+                "the type `{{Self}}` of the variable {} must implement `FromStr`",
+                it.name()
+            );
+            token_stream.extend(quote! {
+                const _: () = {
+                    #[diagnostic::on_unimplemented(
+                        message = #message,
+                        label = "the value of the variable is parsed with `FromStr`",
+                        note = "the error of `FromStr` must implement `Display`"
+                    )]
+                    trait CustomType {}
+                    impl<T> CustomType for T
+                    where
+                        T: std::str::FromStr,
+                        T::Err: std::fmt::Display,
+                    {
+                    }
+                    fn check<T: CustomType>() {}
+                    let _ = check::<#custom_type>;
+                };
+            });
+        }
+        token_stream
+    }
+
+    // The value of the variable, parsed from the `&str` expression `value` (the content of the
+    // variable or its default value); the parsing errors are returned from `from_env`.
+    fn parsed_value(
+        &self,
+        spec: &EnvVariableSpec,
+        value: TokenStream2,
+        key: TokenStream2,
+    ) -> TokenStream2 {
+        let invalidenvvalueerror_struct_ident = self.invalidenvvalueerror_struct_ident();
+        if spec.is_secret() {
+            // The value is moved in the secret, without copies left behind.
+            let secret_struct_ident = self.secret_struct_ident();
+            return quote! { #secret_struct_ident { value: String::from(#value) } };
+        }
+        let parse_expression = match spec.rust_type() {
+            AcceptedRustType::PathBuf => return self.path_value(spec, value, key),
+            AcceptedRustType::Vec(element_type) => {
+                let separator = spec.separator();
+                let item = if spec.trim() {
+                    quote! { item.trim() }
+                } else {
+                    quote! { item }
+                };
+                let element_type_tokens = self.type_tokens(spec, element_type);
+                let parse_element =
+                    self.parse_expression(spec, element_type, element_type_tokens.clone(), item);
+                quote! {
+                    if value.is_empty() {
+                        Ok(Vec::new())
+                    } else {
+                        value
+                            .split(#separator)
+                            .enumerate()
+                            .map(|(index, item)| {
+                                (#parse_element)
+                                    .map_err(|it| format!("element {}: {}", index, it))
+                            })
+                            .collect::<Result<Vec<#element_type_tokens>, String>>()
+                    }
+                }
+            }
+            rust_type => {
+                self.parse_expression(spec, rust_type, self.value_type(spec), quote! { value })
+            }
+        };
+        // The credentials of the URLs are not shown in the errors.
+        let shown_value = match spec.rust_type().element_type() {
+            AcceptedRustType::Url => {
+                let url_struct_ident = self.url_struct_ident();
+                quote! { &#url_struct_ident::redact_credentials(value) }
+            }
+            _ => quote! { value },
+        };
+        quote! {
+            {
+                let value: &str = &#value;
+                (#parse_expression).map_err(|message| {
+                    #invalidenvvalueerror_struct_ident::new(#key, #shown_value, message)
+                })?
+            }
+        }
+    }
+
+    // The path in the `OsString` or `&str` expression `value`, with the leading `~` expanded to
+    // the home directory and made absolute against the base directory, when requested.
+    fn path_value(
+        &self,
+        spec: &EnvVariableSpec,
+        value: TokenStream2,
+        key: TokenStream2,
+    ) -> TokenStream2 {
+        let invalidenvvalueerror_struct_ident = self.invalidenvvalueerror_struct_ident();
+        let expand_tilde = if spec.expand_tilde() {
+            quote! {
+                let path = match path.strip_prefix("~") {
+                    Ok(relative_path) => {
+                        match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+                            Some(home) => std::path::PathBuf::from(home).join(relative_path),
+                            None => {
+                                return Err(#invalidenvvalueerror_struct_ident::new(
+                                    #key,
+                                    &path.display().to_string(),
+                                    String::from("cannot expand '~', the home directory is not set"),
+                                )
+                                .into())
+                            }
+                        }
+                    }
+                    Err(_) => path,
+                };
+            }
+        } else {
+            TokenStream2::new()
+        };
+        let make_absolute = match spec.base_dir() {
+            Some(base_dir) => quote! {
+                let path = if path.is_relative() {
+                    std::path::Path::new(#base_dir).join(path)
+                } else {
+                    path
+                };
+            },
+            None => TokenStream2::new(),
+        };
+        quote! {
+            {
+                let path = std::path::PathBuf::from(#value);
+                #expand_tilde
+                #make_absolute
+                path
+            }
+        }
+    }
+
+    // A `Result<T, String>` expression parsing the `&str` expression `value` as `rust_type`.
+    fn parse_expression(
+        &self,
+        spec: &EnvVariableSpec,
+        rust_type: &AcceptedRustType,
+        value_type: TokenStream2,
+        value: TokenStream2,
+    ) -> TokenStream2 {
+        if let Some(parser) = spec.parser() {
+            let parser: syn::Path =
+                syn::parse_str(parser).expect("the parsers are checked to be valid paths");
+            return quote! { #parser(#value).map_err(|it| it.to_string()) };
+        }
+        if let AcceptedRustType::bool = rust_type {
+            let truthy = spec.truthy();
+            let falsy = spec.falsy();
+            let expected_bool = spec.expected_bool_message();
+            return quote! {
+                {
+                    let value: &str = #value;
+                    if [#(#truthy),*].iter().any(|it| it.eq_ignore_ascii_case(value)) {
+                        Ok(true)
+                    } else if [#(#falsy),*].iter().any(|it| it.eq_ignore_ascii_case(value)) {
+                        Ok(false)
+                    } else {
+                        Err(String::from(#expected_bool))
+                    }
+                }
+            };
+        }
+        if let (AcceptedRustType::Url, Some(schemes)) = (rust_type, spec.schemes()) {
+            let expected_schemes = spec.expected_schemes_message();
+            return quote! {
+                #value.parse::<#value_type>().and_then(|it| {
+                    if [#(#schemes),*].iter().any(|scheme| scheme.eq_ignore_ascii_case(it.scheme())) {
+                        Ok(it)
+                    } else {
+                        Err(format!("unsupported scheme '{}', {}", it.scheme(), #expected_schemes))
+                    }
+                })
+            };
+        }
+        match rust_type.runtime_helper() {
+            Some(helper) => {
+                let function_name = helper.function_name();
+                quote! { #function_name(#value) }
+            }
+            None => match rust_type.expected_format() {
+                Some(expected_format) => quote! {
+                    #value.parse::<#value_type>().map_err(|_| String::from(#expected_format))
+                },
+                None => quote! { #value.parse::<#value_type>().map_err(|it| it.to_string()) },
+            },
+        }
+    }
+
+    // The runtime helpers needed by the variables, defined once inside `from_env`.
+    fn runtime_helpers_code(&self) -> TokenStream2 {
+        let mut helpers: Vec<RuntimeHelper> = Vec::new();
+        for it in self.variable_declarations.iter() {
+            if let Some(helper) = it.rust_type().runtime_helper() {
+                if !helpers.contains(&helper) {
+                    helpers.push(helper);
+                }
+            }
+        }
+        helpers
+            .into_iter()
+            .map(RuntimeHelper::to_token_stream)
+            .collect()
+    }
+
     fn requiredenvnotfounderror_struct_ident(&self) -> SynIdent {
         format_ident!(
             "{}RequiredEnvNotFoundError",
@@ -139,4 +859,88 @@ impl DeclarativeEnvGenerator {
             impl std::error::Error for #error_struct_name {}
         }
     }
+
+    fn invalidenvvalueerror_struct_ident(&self) -> SynIdent {
+        format_ident!("{}InvalidEnvValueError", self.empty_struct.struct_name())
+    }
+
+    fn invalidenvvalueerror_code(&self) -> TokenStream2 {
+        let error_struct_name = self.invalidenvvalueerror_struct_ident();
+        quote! {
+            #[derive(Debug)]
+            pub struct #error_struct_name {
+                key: String,
+                value: String,
+                message: String,
+            }
+
+            impl #error_struct_name {
+                fn new(key: &str, value: &str, message: String) -> Self {
+                    Self { key: key.to_string(), value: value.to_string(), message }
+                }
+            }
+
+            impl std::fmt::Display for #error_struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(
+                    f,
+                    "failed to load configuration from env: invalid value '{}' for variable {}: {}",
+                    self.value,
+                    self.key,
+                    self.message,
+                    )
+                }
+            }
+
+            impl std::error::Error for #error_struct_name {}
+        }
+    }
+
+    fn envfileerror_struct_ident(&self) -> SynIdent {
+        format_ident!("{}EnvFileError", self.empty_struct.struct_name())
+    }
+
+    // The error of the variables read from a file, generated only when one of them is declared.
+    fn envfileerror_code(&self) -> TokenStream2 {
+        if !self
+            .variable_declarations
+            .iter()
+            .any(|it| it.file_fallback())
+        {
+            return TokenStream2::new();
+        }
+        let error_struct_name = self.envfileerror_struct_ident();
+        quote! {
+            #[derive(Debug)]
+            pub struct #error_struct_name {
+                key: String,
+                path: String,
+                message: String,
+            }
+
+            impl #error_struct_name {
+                fn new(key: &str, path: &std::ffi::OsStr, message: String) -> Self {
+                    Self {
+                        key: key.to_string(),
+                        path: std::path::Path::new(path).display().to_string(),
+                        message,
+                    }
+                }
+            }
+
+            impl std::fmt::Display for #error_struct_name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(
+                    f,
+                    "failed to load configuration from env: cannot read variable {} from '{}': {}",
+                    self.key,
+                    self.path,
+                    self.message,
+                    )
+                }
+            }
+
+            impl std::error::Error for #error_struct_name {}
+        }
+    }
 }
@@ -0,0 +1,314 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use hierrorchy::error_leaf;
+use proc_macro2::Span;
+use syn::Error as SynError;
+
+use crate::{
+    dotenv_definition,
+    macro_config::{AcceptedFormat, DefinitionFile, UnknownFormatExtensionError},
+    variable_declarations::EnvVariableDeclarations,
+};
+
+const WORKSPACE_ANCHOR: &str = "WORKSPACE";
+
+pub struct DefinitionLoader {
+    manifest_dir: PathBuf,
+    read_files: Vec<PathBuf>,
+}
+
+impl DefinitionLoader {
+    pub fn new() -> Result<Self, SynError> {
+        let cargo_manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+            .map_err(|it| SynError::new(Span::call_site(), it))?;
+        Ok(DefinitionLoader {
+            manifest_dir: PathBuf::from(cargo_manifest_dir),
+            read_files: Vec::new(),
+        })
+    }
+
+    /// The canonical paths of all the definition files read so far, included ones as well.
+    pub fn read_files(&self) -> &[PathBuf] {
+        &self.read_files
+    }
+
+    pub fn load(
+        &mut self,
+        files: &[DefinitionFile],
+        section: Option<&str>,
+    ) -> Result<EnvVariableDeclarations, SynError> {
+        let mut declarations = EnvVariableDeclarations::default();
+        let mut origins: HashMap<String, &str> = HashMap::new();
+        for file in files {
+            let file_declarations = self.load_file(
+                &self.resolve_path(file.path(), &self.manifest_dir)?,
+                file.format(),
+                section,
+                &mut Vec::new(),
+            )?;
+            for it in file_declarations.iter() {
+                if let Some(first_file) = origins.insert(it.name().to_string(), file.path()) {
+                    return Err(SynError::new(
+                        Span::call_site(),
+                        DuplicateVariableError {
+                            name: it.name().to_string(),
+                            first_file: first_file.to_string(),
+                            second_file: file.path().to_string(),
+                        },
+                    ));
+                }
+            }
+            declarations.extend(file_declarations);
+        }
+        Ok(declarations)
+    }
+
+    pub fn load_inline(
+        &mut self,
+        declarations: EnvVariableDeclarations,
+        section: Option<&str>,
+    ) -> Result<EnvVariableDeclarations, SynError> {
+        let declarations = select_section(declarations, section, "the inline definition")?;
+        let manifest_dir = self.manifest_dir.clone();
+        self.resolve_includes(declarations, &manifest_dir, None, &mut Vec::new())
+    }
+
+    fn load_file(
+        &mut self,
+        path: &Path,
+        format: AcceptedFormat,
+        section: Option<&str>,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<EnvVariableDeclarations, SynError> {
+        let read_error = |it: std::io::Error| {
+            SynError::new(
+                Span::call_site(),
+                format!("failed to read '{}': {}", path.display(), it),
+            )
+        };
+        let canonical_path = path.canonicalize().map_err(read_error)?;
+        if include_stack.contains(&canonical_path) {
+            let chain: Vec<String> = include_stack
+                .iter()
+                .chain(std::iter::once(&canonical_path))
+                .map(|it| self.display_path(it))
+                .collect();
+            return Err(SynError::new(
+                Span::call_site(),
+                IncludeCycleError {
+                    chain: chain.join(" -> "),
+                },
+            ));
+        }
+        let content = std::fs::read_to_string(&canonical_path).map_err(read_error)?;
+        if !self.read_files.contains(&canonical_path) {
+            self.read_files.push(canonical_path.clone());
+        }
+        let declarations = parse_env_definition(format, &content).map_err(|it| {
+            SynError::new(
+                Span::call_site(),
+                format!("failed to parse '{}': {}", self.display_path(path), it),
+            )
+        })?;
+        let declarations = select_section(
+            declarations,
+            section,
+            &format!("'{}'", self.display_path(path)),
+        )?;
+        let base_dir = canonical_path
+            .parent()
+            .expect("a canonical file path always has a parent")
+            .to_path_buf();
+        include_stack.push(canonical_path);
+        let resolved = self.resolve_includes(declarations, &base_dir, Some(format), include_stack);
+        include_stack.pop();
+        resolved
+    }
+
+    // Included files are resolved relative to `base_dir` and applied in order, so that later
+    // files (and finally the including one) override the variables declared by earlier ones.
+    fn resolve_includes(
+        &mut self,
+        mut declarations: EnvVariableDeclarations,
+        base_dir: &Path,
+        including_format: Option<AcceptedFormat>,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<EnvVariableDeclarations, SynError> {
+        let includes = declarations.take_includes();
+        if includes.is_empty() {
+            return Ok(declarations);
+        }
+        let mut resolved = EnvVariableDeclarations::default();
+        for include in includes {
+            let path = self.resolve_path(&include, base_dir)?;
+            let format = AcceptedFormat::from_path(&path)
+                .or(including_format)
+                .ok_or_else(|| {
+                    SynError::new(
+                        Span::call_site(),
+                        UnknownFormatExtensionError::new(include.clone()),
+                    )
+                })?;
+            resolved.override_with(self.load_file(&path, format, None, include_stack)?);
+        }
+        resolved.override_with(declarations);
+        Ok(resolved)
+    }
+
+    // A path starting with `$NAME/` is anchored to the workspace root (`$WORKSPACE`) or to the
+    // directory in the environment variable `NAME` (e.g. `$OUT_DIR`), instead of `base_dir`.
+    fn resolve_path(&self, path: &str, base_dir: &Path) -> Result<PathBuf, SynError> {
+        let Some(anchored) = path.strip_prefix('$') else {
+            return Ok(base_dir.join(path));
+        };
+        let (anchor, relative_path) = anchored.split_once('/').unwrap_or((anchored, ""));
+        let anchor_dir = if anchor == WORKSPACE_ANCHOR {
+            self.workspace_dir()
+        } else {
+            std::env::var_os(anchor).map(PathBuf::from).ok_or_else(|| {
+                SynError::new(
+                    Span::call_site(),
+                    UndefinedPathAnchorError {
+                        anchor: anchor.to_string(),
+                        path: path.to_string(),
+                    },
+                )
+            })?
+        };
+        Ok(anchor_dir.join(relative_path))
+    }
+
+    // The workspace root is the closest ancestor of the manifest dir whose `Cargo.toml` declares
+    // a `[workspace]`; a crate outside of any workspace is its own root.
+    fn workspace_dir(&self) -> PathBuf {
+        self.manifest_dir
+            .ancestors()
+            .find(|dir| {
+                std::fs::read_to_string(dir.join("Cargo.toml"))
+                    .is_ok_and(|manifest| manifest.lines().any(is_workspace_header))
+            })
+            .unwrap_or(&self.manifest_dir)
+            .to_path_buf()
+    }
+
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.manifest_dir)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+// Whether the manifest line opens the `[workspace]` table (or one of its subtables, such as
+// `[workspace.dependencies]`), ignoring the spacing and a trailing comment.
+fn is_workspace_header(line: &str) -> bool {
+    let line = line.split_once('#').map_or(line, |it| it.0).trim();
+    line.strip_prefix('[')
+        .and_then(|it| it.strip_suffix(']'))
+        .map(str::trim)
+        .is_some_and(|table| table == "workspace" || table.starts_with("workspace."))
+}
+
+fn select_section(
+    mut declarations: EnvVariableDeclarations,
+    section: Option<&str>,
+    source: &str,
+) -> Result<EnvVariableDeclarations, SynError> {
+    match section {
+        Some(section) => declarations.take_section(section).ok_or_else(|| {
+            SynError::new(
+                Span::call_site(),
+                SectionNotFoundError {
+                    section: section.to_string(),
+                    source: source.to_string(),
+                },
+            )
+        }),
+        None => Ok(declarations),
+    }
+}
+
+fn parse_env_definition(
+    format: AcceptedFormat,
+    content: &str,
+) -> Result<EnvVariableDeclarations, SynError> {
+    match format {
+        AcceptedFormat::Hjson => {
+            deser_hjson::from_str(content).map_err(|it| SynError::new(Span::call_site(), it))
+        }
+        AcceptedFormat::Dotenv => {
+            dotenv_definition::from_str(content).map_err(|it| SynError::new(Span::call_site(), it))
+        }
+        #[cfg(feature = "toml")]
+        AcceptedFormat::Toml => {
+            toml::from_str(content).map_err(|it| SynError::new(Span::call_site(), it))
+        }
+        #[cfg(feature = "yaml")]
+        AcceptedFormat::Yaml => {
+            serde_yaml::from_str(content).map_err(|it| SynError::new(Span::call_site(), it))
+        }
+        #[cfg(feature = "json")]
+        AcceptedFormat::Json => {
+            let mut value: serde_json::Value =
+                serde_json::from_str(content).map_err(|it| SynError::new(Span::call_site(), it))?;
+            stringify_json_default_values(&mut value);
+            serde_json::from_value(value).map_err(|it| SynError::new(Span::call_site(), it))
+        }
+        #[cfg(feature = "json5")]
+        AcceptedFormat::Json5 => {
+            json5::from_str(content).map_err(|it| SynError::new(Span::call_site(), it))
+        }
+    }
+}
+
+/// Replaces numeric and boolean `default` values with their JSON text, since `serde_json` (unlike the
+/// other formats) refuses to deserialize them as strings.
+#[cfg(feature = "json")]
+fn stringify_json_default_values(value: &mut serde_json::Value) {
+    if let serde_json::Value::Object(map) = value {
+        for (key, entry) in map.iter_mut() {
+            match entry {
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) if key == "default" => {
+                    *entry = serde_json::Value::String(entry.to_string());
+                }
+                _ => stringify_json_default_values(entry),
+            }
+        }
+    }
+}
+
+#[error_leaf(format!(
+    "variable '{}' is declared both in '{}' and in '{}'",
+    self.name,
+    self.first_file,
+    self.second_file,
+))]
+pub struct DuplicateVariableError {
+    name: String,
+    first_file: String,
+    second_file: String,
+}
+
+#[error_leaf(format!("include cycle detected: {}", self.chain))]
+pub struct IncludeCycleError {
+    chain: String,
+}
+
+#[error_leaf(format!("section '{}' not found in {}", self.section, self.source))]
+pub struct SectionNotFoundError {
+    section: String,
+    source: String,
+}
+
+#[error_leaf(format!(
+    "cannot resolve '{}': the environment variable '{}' is not set",
+    self.path,
+    self.anchor,
+))]
+pub struct UndefinedPathAnchorError {
+    anchor: String,
+    path: String,
+}
@@ -0,0 +1,187 @@
+use hierrorchy::{error_leaf, error_node};
+use serde::{
+    de::{
+        value::{Error as SerdeValueError, MapDeserializer, SeqDeserializer, StringDeserializer},
+        Error as _, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use std::{collections::BTreeMap, error::Error};
+
+use crate::variable_declarations::EnvVariableDeclarations;
+
+const TYPE_ANNOTATION: &str = "type";
+const DEFAULT_TYPE: &str = "String";
+
+/// Parses a dotenv file (e.g. `.env.example`) as a variable definition file.
+///
+/// The comment block right above a `KEY=value` line is the description of the variable, except
+/// for the lines starting with `@`, which are annotations in the form `@<field> <value>` (e.g.
+/// `# @type u16`). The value, when not empty, is the default value of the variable; a variable
+/// without a `@type` annotation is a `String`.
+pub fn from_str(content: &str) -> Result<EnvVariableDeclarations, DotenvDefinitionError> {
+    let mut variables: Vec<(String, BTreeMap<String, String>)> = Vec::new();
+    let mut description_lines: Vec<&str> = Vec::new();
+    let mut annotations: BTreeMap<String, String> = BTreeMap::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            description_lines.clear();
+            annotations.clear();
+        } else if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.trim();
+            if let Some(annotation) = comment.strip_prefix('@') {
+                let (key, value) = annotation.split_once(char::is_whitespace).ok_or_else(|| {
+                    InvalidDotenvLineError {
+                        line: index + 1,
+                        message: format!("annotation '@{}' has no value", annotation),
+                    }
+                })?;
+                annotations.insert(key.to_string(), value.trim().to_string());
+            } else if !comment.trim_start_matches('#').is_empty() {
+                description_lines.push(comment);
+            }
+        } else {
+            let assignment = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) =
+                assignment
+                    .split_once('=')
+                    .ok_or_else(|| InvalidDotenvLineError {
+                        line: index + 1,
+                        message: String::from("expected a 'KEY=value' assignment"),
+                    })?;
+            let name = name.trim();
+            if description_lines.is_empty() {
+                return Err(InvalidDotenvLineError {
+                    line: index + 1,
+                    message: format!("variable '{}' has no description comment", name),
+                }
+                .into());
+            }
+            let mut fields = std::mem::take(&mut annotations);
+            fields
+                .entry(TYPE_ANNOTATION.to_string())
+                .or_insert_with(|| DEFAULT_TYPE.to_string());
+            fields.insert(String::from("description"), description_lines.join(" "));
+            if let Some(default_value) = parse_value(value.trim()) {
+                fields.insert(String::from("default"), default_value);
+            }
+            description_lines.clear();
+            variables.push((name.to_string(), fields));
+        }
+    }
+
+    let deserializer: MapDeserializer<_, SerdeValueError> =
+        MapDeserializer::new(variables.into_iter().map(|(name, fields)| {
+            let fields: BTreeMap<String, DotenvValue> = fields
+                .into_iter()
+                .map(|(key, value)| (key, DotenvValue(value)))
+                .collect();
+            (name, fields)
+        }));
+    Ok(EnvVariableDeclarations::deserialize(deserializer)?)
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    for quote in ['"', '\''] {
+        if let Some((unquoted, _rest)) = value
+            .strip_prefix(quote)
+            .and_then(|it| it.split_once(quote))
+        {
+            return Some(unquoted.to_string());
+        }
+    }
+    let unquoted = match value.split_once(" #") {
+        Some((v, _comment)) => v.trim_end(),
+        None => value,
+    };
+    if unquoted.is_empty() {
+        None
+    } else {
+        Some(unquoted.to_string())
+    }
+}
+
+// The annotations are plain strings, which are converted to the type expected by the
+// declaration field: booleans are `true` or `false`, lists are comma-separated.
+struct DotenvValue(String);
+
+impl IntoDeserializer<'_, SerdeValueError> for DotenvValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for DotenvValue {
+    type Error = SerdeValueError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0.parse::<bool>() {
+            Ok(v) => visitor.visit_bool(v),
+            Err(_) => Err(SerdeValueError::custom(format!(
+                "invalid boolean '{}', expected 'true' or 'false'",
+                self.0
+            ))),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let items: Vec<DotenvValue> = self
+            .0
+            .split(',')
+            .map(|it| DotenvValue(it.trim().to_string()))
+            .collect();
+        visitor.visit_seq(SeqDeserializer::new(items.into_iter()))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        StringDeserializer::<SerdeValueError>::new(self.0).deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+error_node! {
+    pub type DotenvDefinitionError<InvalidDotenvLineError, SerdeValueError> = "invalid dotenv definition"
+}
+
+#[error_leaf(format!("line {}: {}", self.line, self.message))]
+pub struct InvalidDotenvLineError {
+    line: usize,
+    message: String,
+}
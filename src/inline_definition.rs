@@ -0,0 +1,320 @@
+use std::fmt::Display;
+
+use proc_macro2::Span;
+use serde::{
+    de::{value::StringDeserializer, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use syn::{
+    braced, bracketed, ext::IdentExt, parse::Parse, token, Error as SynError, Ident, Lit, LitBool,
+    LitStr, Token,
+};
+
+use crate::variable_declarations::EnvVariableDeclarations;
+
+/// A variable definition map written directly in the macro invocation, with the same structure
+/// of the definition files (e.g. `{ SERVER_PORT: { type: u16, description: "...", default: 8080 } }`).
+pub struct InlineDefinition {
+    root: InlineValue,
+}
+
+impl InlineDefinition {
+    pub fn to_declarations(&self) -> Result<EnvVariableDeclarations, SynError> {
+        let mut declarations = EnvVariableDeclarations::deserialize(&self.root)
+            .map_err(|it| SynError::new(it.span.unwrap_or(self.root.span()), it.message))?;
+        set_spans(&self.root, &mut declarations);
+        Ok(declarations)
+    }
+}
+
+// Attaches to each variable the span of its configuration, so that its validation errors point
+// to it instead of the whole attribute.
+fn set_spans(value: &InlineValue, declarations: &mut EnvVariableDeclarations) {
+    if let InlineValue::Map(entries, _) = value {
+        for (key, value) in entries {
+            declarations.set_span(&key.name, value.span());
+            if let Some(section) = declarations.section_mut(&key.name) {
+                set_spans(value, section);
+            }
+        }
+    }
+}
+
+impl Parse for InlineDefinition {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if !input.peek(token::Brace) {
+            return Err(input.error("expected a map of variable definitions"));
+        }
+        Ok(InlineDefinition {
+            root: input.parse()?,
+        })
+    }
+}
+
+enum InlineValue {
+    Map(Vec<(InlineKey, InlineValue)>, Span),
+    List(Vec<InlineValue>, Span),
+    String(String, Span),
+    Integer(String, Span),
+    Float(String, Span),
+    Bool(bool, Span),
+}
+
+impl InlineValue {
+    fn span(&self) -> Span {
+        match self {
+            InlineValue::Map(_, span)
+            | InlineValue::List(_, span)
+            | InlineValue::String(_, span)
+            | InlineValue::Integer(_, span)
+            | InlineValue::Float(_, span)
+            | InlineValue::Bool(_, span) => *span,
+        }
+    }
+}
+
+impl Parse for InlineValue {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(token::Brace) {
+            let content;
+            let brace = braced!(content in input);
+            let mut entries = Vec::new();
+            while !content.is_empty() {
+                let key: InlineKey = content.parse()?;
+                let _: Token![:] = content.parse()?;
+                let value: InlineValue = content.parse()?;
+                entries.push((key, value));
+                if !content.is_empty() {
+                    let _: Token![,] = content.parse()?;
+                }
+            }
+            Ok(InlineValue::Map(entries, brace.span.join()))
+        } else if input.peek(token::Bracket) {
+            let content;
+            let bracket = bracketed!(content in input);
+            let items = content.parse_terminated(InlineValue::parse, Token![,])?;
+            Ok(InlineValue::List(
+                items.into_iter().collect(),
+                bracket.span.join(),
+            ))
+        } else if input.peek(Token![-]) {
+            let _: Token![-] = input.parse()?;
+            match input.parse()? {
+                Lit::Int(v) => Ok(InlineValue::Integer(
+                    format!("-{}", v.base10_digits()),
+                    v.span(),
+                )),
+                Lit::Float(v) => Ok(InlineValue::Float(
+                    format!("-{}", v.base10_digits()),
+                    v.span(),
+                )),
+                other => Err(SynError::new(other.span(), "expected a number")),
+            }
+        } else if input.peek(LitBool) {
+            let v: LitBool = input.parse()?;
+            Ok(InlineValue::Bool(v.value, v.span()))
+        } else if input.peek(Ident::peek_any) {
+            let word = input.call(Ident::parse_any)?;
+            Ok(InlineValue::String(word.to_string(), word.span()))
+        } else {
+            match input.parse()? {
+                Lit::Str(v) => Ok(InlineValue::String(v.value(), v.span())),
+                Lit::Int(v) => Ok(InlineValue::Integer(
+                    v.base10_digits().to_string(),
+                    v.span(),
+                )),
+                Lit::Float(v) => Ok(InlineValue::Float(v.base10_digits().to_string(), v.span())),
+                other => Err(SynError::new(
+                    other.span(),
+                    "expected a string, a number, a boolean, a list or a map",
+                )),
+            }
+        }
+    }
+}
+
+struct InlineKey {
+    name: String,
+    span: Span,
+}
+
+impl Parse for InlineKey {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            let key: LitStr = input.parse()?;
+            Ok(InlineKey {
+                name: key.value(),
+                span: key.span(),
+            })
+        } else {
+            let key = input.call(Ident::parse_any)?;
+            Ok(InlineKey {
+                name: key.to_string(),
+                span: key.span(),
+            })
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InlineDefinitionError {
+    message: String,
+    span: Option<Span>,
+}
+
+impl InlineDefinitionError {
+    fn or_span(self, span: Span) -> Self {
+        InlineDefinitionError {
+            span: self.span.or(Some(span)),
+            ..self
+        }
+    }
+}
+
+impl Display for InlineDefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for InlineDefinitionError {}
+
+impl serde::de::Error for InlineDefinitionError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        InlineDefinitionError {
+            message: msg.to_string(),
+            span: None,
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for &InlineValue {
+    type Error = InlineDefinitionError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self {
+            InlineValue::Map(entries, _) => visitor.visit_map(InlineMapAccess {
+                entries: entries.iter(),
+                value: None,
+            }),
+            InlineValue::List(items, _) => visitor.visit_seq(InlineSeqAccess {
+                items: items.iter(),
+            }),
+            InlineValue::String(v, _) => visitor.visit_str(v),
+            InlineValue::Integer(v, _) => match (v.parse::<u64>(), v.parse::<i64>()) {
+                (Ok(unsigned), _) => visitor.visit_u64(unsigned),
+                (_, Ok(signed)) => visitor.visit_i64(signed),
+                _ => visitor.visit_str(v),
+            },
+            InlineValue::Float(v, _) => match v.parse::<f64>() {
+                Ok(float) => visitor.visit_f64(float),
+                Err(_) => visitor.visit_str(v),
+            },
+            InlineValue::Bool(v, _) => visitor.visit_bool(*v),
+        };
+        result.map_err(|it| it.or_span(self.span()))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            InlineValue::String(v, span) => visitor
+                .visit_enum(StringDeserializer::<InlineDefinitionError>::new(v.clone()))
+                .map_err(|it| it.or_span(*span)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct InlineMapAccess<'a> {
+    entries: std::slice::Iter<'a, (InlineKey, InlineValue)>,
+    value: Option<&'a InlineValue>,
+}
+
+impl<'de, 'a> MapAccess<'de> for InlineMapAccess<'a> {
+    type Error = InlineDefinitionError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(StringDeserializer::<InlineDefinitionError>::new(
+                    key.name.clone(),
+                ))
+                .map(Some)
+                .map_err(|it| it.or_span(key.span))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed is always called after next_key_seed");
+        seed.deserialize(value)
+            .map_err(|it| it.or_span(value.span()))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct InlineSeqAccess<'a> {
+    items: std::slice::Iter<'a, InlineValue>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for InlineSeqAccess<'a> {
+    type Error = InlineDefinitionError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some(item) => seed
+                .deserialize(item)
+                .map(Some)
+                .map_err(|it| it.or_span(item.span())),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
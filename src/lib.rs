@@ -30,47 +30,262 @@
 //! }
 //! ```
 //!
+//! For small binaries, the variables can also be defined inside the macro invocation with the
+//! `inline` keyword, using the same structure of the definition files:
+//! ```ignore
+//!    #[declarative_env(inline = {
+//!        SERVER_PORT: { type: u16, description: "The port the server will listen on", default: 8080 },
+//!    })]
+//!    struct MyConfig;
+//! ```
+//! Keys can be identifiers or string literals, while values can be literals, single words
+//! (e.g. `u16`), lists (`[...]`) or maps (`{...}`).
+//!
 //! The path of the definition file is relative to the cargo manifest dir (the one with
-//! `Cargo.toml`).
+//! `Cargo.toml`), unless it starts with an anchor:
+//! - `$WORKSPACE/...` is relative to the root of the cargo workspace (e.g.
+//!   `path = "$WORKSPACE/config/env.hjson"`), so that crates of the same workspace can share a
+//!   definition file;
+//! - `$<NAME>/...` is relative to the directory in the environment variable `NAME` at compile
+//!   time (e.g. `path = "$OUT_DIR/env.hjson"` for a definition file generated by a build script).
+//!
+//! `path` also accepts a list of files, possibly in different formats, which variables are merged
+//! into the same struct (e.g. `path = ["../common/env.hjson", "./env.toml"]`); a variable can be
+//! declared in only one of them.
+//!
+//! Every definition file read by the macro (included ones as well) is embedded in the generated
+//! code with `include_bytes!`, so that editing any of them triggers the recompilation of the
+//! crate.
 //!
 //! The supported formats are specified in the `AcceptedFormat` enum; formats are the lowercase
 //! version of the enum variants, so `AcceptedFormat::Hjson` is selected with `format = "hjson"`.
+//! When `format` is omitted, it is inferred from the extension of `path` (e.g. `.toml` selects
+//! `AcceptedFormat::Toml`, `.yml` and `.yaml` select `AcceptedFormat::Yaml`); files named `.env`,
+//! `.env.<suffix>` (e.g. `.env.example`, but not `.env.local.toml`) or `<name>.env` select
+//! `AcceptedFormat::Dotenv`.
 //!
 //! ## Env Variable Definition
 //! The env variable definition file contains a map of objects, where the key is the name of the
 //! env variable, and the value contains the metadata of the variable:
 //! - the **type** (accepted types are defined in `AcceptedRustType`).
 //! - the **description**, unused in generated code, required for documentation purpose.
-//! - the **default** value, optional (it must be parsable as the defiend **type**).
+//! - the **default** value, optional (it must be parsable as the defiend **type**, which is checked
+//!   when the macro is expanded).
+//! - **required**, optional (`true` by default); when `false`, the variable can be missing, so its
+//!   field is an `Option` (e.g. `Option<u16>`) and its getter returns `Option<u16>` (`Option<&str>`
+//!   for `String`). A variable that is not required cannot have a default value.
+//!
+//! ### Sections
+//! The variables can be grouped in nested maps (sections), to generate several structs from the
+//! same definition file; the `section` keyword selects the map to use with a dotted key path:
+//! ```ignore
+//!    #[declarative_env(path = "./env.hjson", section = "database")]
+//!    struct DatabaseConfig;
+//! ```
+//! A map is a section when its first key is not a field of a variable definition (e.g. `type`).
+//! With multiple files, the section is selected in each of them, while included files are
+//! always used as a whole.
+//!
+//! ### Including other files
+//! A definition file can include the variables of other definition files with the `$include`
+//! (a path or a list of paths) and `$extends` (a single path) directives:
+//! ```hjson
+//! $include: ["../common/logging.hjson"]
+//! LOG_LEVEL: {
+//!    default: debug
+//! }
+//! ```
+//! The included paths are relative to the including file (unless they start with an anchor), and
+//! their format is inferred from their extension (falling back to the format of the including
+//! file). A file can redeclare an included variable specifying only the fields to override (e.g.
+//! its default value or its description). Files including each other in a cycle are reported as
+//! an error.
 //!
 //! ### Accepted formats
 //! This crate supports the following formats for the definition file:
 //! - hjson
+//! - dotenv (see below)
+//! - toml (requires the `toml` feature)
+//! - yaml (requires the `yaml` feature)
+//! - json (requires the `json` feature)
+//! - json5 (requires the `json5` feature)
+//!
+//! ### Dotenv definition files
+//! With `format = "dotenv"`, an existing `.env.example` file can be used as the definition file:
+//! the comment block right above each `KEY=value` line is the description of the variable, the
+//! value (if not empty) is its default value, and the comment lines starting with `@` are
+//! annotations for the other fields, such as the type:
+//! ```text
+//! # The port the server will listen on
+//! # @type u16
+//! SERVER_PORT=8080
+//! ```
+//! Variables without the `@type` annotation are `String`s.
 //!
 //! ### Accepted types
 //! This crate supports the following types for the variables defined:
-//! - iX (X = {8, 16, 32, 64, 128})
-//! - uX (X = {8, 16, 32, 64, 128})
-//! - bool
+//! - iX (X = {8, 16, 32, 64, 128, size})
+//! - uX (X = {8, 16, 32, 64, 128, size})
+//! - NonZeroIX, NonZeroUX (X = {8, 16, 32, 64, 128, size}; the types of `std::num`, which
+//!   reject `0`)
+//! - bool (see below)
+//! - char
 //! - String
 //! - f32,f64
+//! - Duration (`std::time::Duration`, written as a sequence of amounts and units, e.g. `30s`, `5m`,
+//!   `1h30m` or `250ms`; the supported units are `ns`, `us`, `ms`, `s`, `m`, `h` and `d`, and `0`
+//!   needs no unit)
+//! - ByteSize (a `u64` number of bytes, optionally followed by a decimal unit, `kB`, `MB`, `GB`,
+//!   `TB`, `PB`, or a binary unit, `KiB`, `MiB`, `GiB`, `TiB`, `PiB`; e.g. `512`, `64KiB`, `10MB`)
+//! - IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr (the types of `std::net`)
+//! - HostPort (a `host:port` pair, where the host is a DNS name, an IPv4 address or an IPv6
+//!   address in square brackets, e.g. `db.internal:5432` or `[::1]:8080`; the macro generates
+//!   a struct named after the struct, e.g. `MyConfigHostPort`, with the `host()` and `port()`
+//!   getters)
+//! - PathBuf (see below)
+//! - Url (see below)
+//! - a type implementing `FromStr` (see below)
+//! - `Vec<T>`, where `T` is one of the types above
+//! - enum (see below)
+//!
+//! The value of a list variable is split by its **separator** field (`,` by default) and each
+//! element is parsed as `T`; with `trim: true` the whitespaces around the elements are removed.
+//! The getter of a list variable returns a slice (e.g. `&[u16]`):
+//! ```hjson
+//! KAFKA_BROKERS: {
+//!    type: Vec<String>
+//!    description: The addresses of the Kafka brokers
+//!    separator: " "
+//! }
+//! ```
+//!
+//! A `bool` variable accepts `true`, `1`, `yes` and `on` as `true`, and `false`, `0`, `no` and
+//! `off` as `false`, ignoring the ASCII case; any other value is rejected with an error listing
+//! the accepted ones. The values can be replaced for all the bool variables with the
+//! `bool_truthy` and `bool_falsy` keywords of the macro (e.g. `bool_truthy = ["y", "yes"]`), and
+//! for a single variable with its **truthy** and **falsy** fields; a value cannot be both truthy
+//! and falsy:
+//! ```hjson
+//! MAINTENANCE_MODE: {
+//!    type: bool
+//!    description: Whether the requests are rejected with 503
+//!    truthy: ["enabled"]
+//!    falsy: ["disabled"]
+//! }
+//! ```
+//!
+//! A `String` variable with `secret: true` is wrapped in a struct named after the struct (e.g.
+//! `MyConfigSecret`), which `Debug` and `Display` implementations print `[REDACTED]`: the value
+//! is only available with its `expose()` method, it is never shown in the error messages, and its
+//! memory is overwritten with zeros when it is dropped:
+//! ```hjson
+//! DB_PASSWORD: {
+//!    type: String
+//!    description: The password of the database user
+//!    secret: true
+//! }
+//! ```
+//!
+//! With `file_fallback: true`, a variable can also be read from a file, following the convention
+//! of Docker and Kubernetes secrets: when the variable `<NAME>_FILE` is set (e.g.
+//! `DB_PASSWORD_FILE=/run/secrets/db`), the value is the content of the file it points to,
+//! without the trailing newline. Setting both `<NAME>` and `<NAME>_FILE` is an error, and the
+//! errors of a value read from a file name the file. The `file_fallback = true` keyword of the
+//! macro enables it for all the variables which do not set the field.
+//!
+//! A `PathBuf` variable is read as it is, even when its value is not valid UTF-8, and its getter
+//! returns a `&Path`. With `expand_tilde: true` a leading `~` is replaced by the home directory,
+//! while with `base_dir` (an absolute path) the relative paths are resolved against it:
+//! ```hjson
+//! DATA_DIR: {
+//!    type: PathBuf
+//!    description: The directory the application stores its data in
+//!    base_dir: /var/lib/app
+//!    default: data
+//! }
+//! ```
+//!
+//! A `Url` variable is an absolute URL with an authority (e.g. `postgres://db:5432/app`); the
+//! macro generates a struct named after the struct (e.g. `MyConfigUrl`), with getters for the
+//! components of the URL (returned as written, without decoding the percent-encoded characters)
+//! and `as_str()` for the whole URL. The credentials of the URL are redacted in its `Debug` and
+//! `Display` output and in the error messages. The accepted schemes can be restricted with the
+//! **schemes** field:
+//! ```hjson
+//! DATABASE_URL: {
+//!    type: Url
+//!    description: The URL of the database
+//!    schemes: ["postgres", "postgresql"]
+//! }
+//! ```
+//!
+//! An `enum` variable accepts only one of its **values**, optionally ignoring the ASCII case with
+//! `case_insensitive: true`. The macro generates a dedicated enum named after the struct and the
+//! variable, with a variant for each value and the `FromStr` and `Display` implementations; the
+//! name must differ from the other types generated for the struct (e.g. `MyConfigUrl`):
+//! ```hjson
+//! LOG_FORMAT: {
+//!    type: enum
+//!    description: The format of the logs
+//!    values: ["json", "pretty", "compact"]
+//! }
+//! ```
+//! generates, for the struct `MyConfig`, the enum
+//! ```
+//! enum MyConfigLogFormat {
+//!    Json,
+//!    Pretty,
+//!    Compact,
+//! }
+//! ```
+//!
+//! Any other type implementing `FromStr` (with an error implementing `Display`), `Debug` and
+//! `Clone` can be used with its path, either as `{ path: "crate::Region" }` or with the `custom:`
+//! prefix (e.g. `custom:tracing::Level`); its getter returns a reference. The default value of
+//! such a variable is only parsed when the configuration is loaded:
+//! ```hjson
+//! AWS_REGION: {
+//!    type: { path: "crate::Region" }
+//!    description: The region of the AWS services
+//!    default: eu-west-1
+//! }
+//! ```
+//!
+//! A value which cannot be parsed with `FromStr` can be parsed by a function named in the
+//! **parser** field, with the signature `fn(&str) -> Result<T, E>`, where `T` is the type of the
+//! variable (or of the elements of a list) and `E` implements `Display`. Its errors are returned
+//! by `from_env` together with the name of the variable, and the default value is only parsed when
+//! the configuration is loaded. The types generated by the macro and `PathBuf` have no parser:
+//! ```hjson
+//! ROUTING_WEIGHTS: {
+//!    type: custom:crate::config::Weights
+//!    description: The share of the requests sent to each upstream
+//!    parser: crate::config::parse_weights
+//! }
+//! ```
 #![deny(missing_docs)]
 
 mod accepted_rust_type;
 mod declarative_env_generator;
+mod definition_loader;
+mod dotenv_definition;
 mod empty_struct;
+mod inline_definition;
 mod macro_config;
+mod naming;
+mod runtime_helpers;
 mod variable_declarations;
 
-use std::path::PathBuf;
-
 use proc_macro::TokenStream;
-use proc_macro2::Span;
+use std::path::PathBuf;
 use syn::{parse_macro_input, Error as SynError};
 
 use crate::{
-    declarative_env_generator::DeclarativeEnvGenerator, empty_struct::EmptyStruct,
-    macro_config::MacroConfig, variable_declarations::EnvVariableDeclarations,
+    declarative_env_generator::DeclarativeEnvGenerator,
+    definition_loader::DefinitionLoader,
+    empty_struct::EmptyStruct,
+    macro_config::{DefinitionSource, MacroConfig},
+    variable_declarations::EnvVariableDeclarations,
 };
 
 extern crate proc_macro;
@@ -79,30 +294,28 @@ extern crate proc_macro;
 #[proc_macro_attribute]
 pub fn declarative_env(attr: TokenStream, item: TokenStream) -> TokenStream {
     let macro_config = parse_macro_input!(attr as MacroConfig);
-    let file_content = match read_env_definition_file(macro_config.path()) {
-        Ok(c) => c,
+    let (variable_configs, definition_files) = match load_variable_declarations(&macro_config) {
+        Ok(v) => v,
         Err(e) => return e.to_compile_error().into(),
     };
 
     let empty_struct = parse_macro_input!(item as EmptyStruct);
-    let variable_configs: EnvVariableDeclarations = match macro_config.format() {
-        macro_config::AcceptedFormat::Hjson => match deser_hjson::from_str(&file_content) {
-            Ok(v) => v,
-            Err(e) => {
-                return SynError::new(Span::call_site(), e)
-                    .to_compile_error()
-                    .into()
-            }
-        },
-    };
-
-    DeclarativeEnvGenerator::new(empty_struct, variable_configs).generate()
+    DeclarativeEnvGenerator::new(empty_struct, variable_configs, definition_files).generate()
 }
 
 #[doc(hidden)]
-fn read_env_definition_file(path: &str) -> Result<String, SynError> {
-    let cargo_manifest_dir =
-        std::env::var("CARGO_MANIFEST_DIR").map_err(|it| SynError::new(Span::call_site(), it))?;
-    let config_file_path = PathBuf::from(cargo_manifest_dir).join(path);
-    std::fs::read_to_string(config_file_path).map_err(|it| SynError::new(Span::call_site(), it))
+fn load_variable_declarations(
+    macro_config: &MacroConfig,
+) -> Result<(EnvVariableDeclarations, Vec<PathBuf>), SynError> {
+    let mut loader = DefinitionLoader::new()?;
+    let mut declarations = match macro_config.source() {
+        DefinitionSource::Files(files) => loader.load(files, macro_config.section())?,
+        DefinitionSource::Inline(definition) => {
+            loader.load_inline(definition.to_declarations()?, macro_config.section())?
+        }
+    };
+    declarations.set_default_bool_values(macro_config.bool_truthy(), macro_config.bool_falsy());
+    declarations.set_default_file_fallback(macro_config.file_fallback());
+    declarations.validate()?;
+    Ok((declarations, loader.read_files().to_vec()))
 }
@@ -1,13 +1,53 @@
 use hierrorchy::{error_leaf, error_node};
-use std::{error::Error, str::FromStr};
-use syn::{parse::Parse, Error as SynError, Ident, LitStr, Token};
+use std::{error::Error, path::Path, str::FromStr};
+use syn::{bracketed, parse::Parse, token, Error as SynError, Ident, LitBool, LitStr, Token};
+
+use crate::{inline_definition::InlineDefinition, variable_declarations};
 
 pub struct MacroConfig {
+    source: DefinitionSource,
+    section: Option<String>,
+    bool_truthy: Option<Vec<String>>,
+    bool_falsy: Option<Vec<String>>,
+    file_fallback: bool,
+}
+
+impl MacroConfig {
+    pub fn source(&self) -> &DefinitionSource {
+        &self.source
+    }
+
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    /// The values read as `true` by the bool variables without their own `truthy` field.
+    pub fn bool_truthy(&self) -> Option<&[String]> {
+        self.bool_truthy.as_deref()
+    }
+
+    /// The values read as `false` by the bool variables without their own `falsy` field.
+    pub fn bool_falsy(&self) -> Option<&[String]> {
+        self.bool_falsy.as_deref()
+    }
+
+    /// Whether the variables without their own `file_fallback` field can be read from a file.
+    pub fn file_fallback(&self) -> bool {
+        self.file_fallback
+    }
+}
+
+pub enum DefinitionSource {
+    Files(Vec<DefinitionFile>),
+    Inline(InlineDefinition),
+}
+
+pub struct DefinitionFile {
     path: String,
     format: AcceptedFormat,
 }
 
-impl MacroConfig {
+impl DefinitionFile {
     pub fn path(&self) -> &str {
         &self.path
     }
@@ -29,11 +69,11 @@ impl Parse for MacroConfig {
             {
                 MacroConfigKeyword::Path => {
                     let _: Token![=] = input.parse()?;
-                    let value: LitStr = input.parse()?;
+                    let paths = parse_string_list(input)?;
                     if !input.is_empty() {
                         let _: Token![,] = input.parse()?;
                     }
-                    macro_config_builder.set_path(value.value());
+                    macro_config_builder.set_paths(paths);
                 }
                 MacroConfigKeyword::Format => {
                     let _: Token![=] = input.parse()?;
@@ -48,6 +88,46 @@ impl Parse for MacroConfig {
                             .map_err(|it| SynError::new(value.span(), it))?,
                     );
                 }
+                MacroConfigKeyword::Inline => {
+                    let _: Token![=] = input.parse()?;
+                    let value: InlineDefinition = input.parse()?;
+                    if !input.is_empty() {
+                        let _: Token![,] = input.parse()?;
+                    }
+                    macro_config_builder.set_inline(value);
+                }
+                MacroConfigKeyword::Section => {
+                    let _: Token![=] = input.parse()?;
+                    let value: LitStr = input.parse()?;
+                    if !input.is_empty() {
+                        let _: Token![,] = input.parse()?;
+                    }
+                    macro_config_builder.set_section(value.value());
+                }
+                MacroConfigKeyword::BoolTruthy => {
+                    let _: Token![=] = input.parse()?;
+                    let values = parse_string_list(input)?;
+                    if !input.is_empty() {
+                        let _: Token![,] = input.parse()?;
+                    }
+                    macro_config_builder.set_bool_truthy(values);
+                }
+                MacroConfigKeyword::BoolFalsy => {
+                    let _: Token![=] = input.parse()?;
+                    let values = parse_string_list(input)?;
+                    if !input.is_empty() {
+                        let _: Token![,] = input.parse()?;
+                    }
+                    macro_config_builder.set_bool_falsy(values);
+                }
+                MacroConfigKeyword::FileFallback => {
+                    let _: Token![=] = input.parse()?;
+                    let value: LitBool = input.parse()?;
+                    if !input.is_empty() {
+                        let _: Token![,] = input.parse()?;
+                    }
+                    macro_config_builder.set_file_fallback(value.value);
+                }
             }
         }
         macro_config_builder
@@ -56,47 +136,162 @@ impl Parse for MacroConfig {
     }
 }
 
+// A string literal or a list of string literals (e.g. `"a"` or `["a", "b"]`).
+fn parse_string_list(input: syn::parse::ParseStream) -> syn::Result<Vec<String>> {
+    if input.peek(token::Bracket) {
+        let content;
+        bracketed!(content in input);
+        Ok(content
+            .parse_terminated(<LitStr as Parse>::parse, Token![,])?
+            .into_iter()
+            .map(|it| it.value())
+            .collect())
+    } else {
+        let value: LitStr = input.parse()?;
+        Ok(vec![value.value()])
+    }
+}
+
 struct MacroConfigBuilder {
-    path: Option<String>,
+    paths: Vec<String>,
     format: Option<AcceptedFormat>,
+    inline: Option<InlineDefinition>,
+    section: Option<String>,
+    bool_truthy: Option<Vec<String>>,
+    bool_falsy: Option<Vec<String>>,
+    file_fallback: bool,
 }
 
 impl MacroConfigBuilder {
     pub fn new() -> Self {
         MacroConfigBuilder {
-            path: None,
+            paths: Vec::new(),
             format: None,
+            inline: None,
+            section: None,
+            bool_truthy: None,
+            bool_falsy: None,
+            file_fallback: false,
         }
     }
 
-    pub fn set_path(&mut self, path: String) {
-        self.path = Some(path);
+    pub fn set_paths(&mut self, paths: Vec<String>) {
+        self.paths = paths;
     }
 
     pub fn set_format(&mut self, format: AcceptedFormat) {
         self.format = Some(format);
     }
 
-    pub fn build(&self) -> Result<MacroConfig, MacroConfigBuilderError> {
-        if self.path.is_none() {
+    pub fn set_inline(&mut self, inline: InlineDefinition) {
+        self.inline = Some(inline);
+    }
+
+    pub fn set_section(&mut self, section: String) {
+        self.section = Some(section);
+    }
+
+    pub fn set_bool_truthy(&mut self, values: Vec<String>) {
+        self.bool_truthy = Some(values);
+    }
+
+    pub fn set_bool_falsy(&mut self, values: Vec<String>) {
+        self.bool_falsy = Some(values);
+    }
+
+    pub fn set_file_fallback(&mut self, file_fallback: bool) {
+        self.file_fallback = file_fallback;
+    }
+
+    pub fn build(self) -> Result<MacroConfig, MacroConfigBuilderError> {
+        if let Some(section) = &self.section {
+            if section.split('.').any(|it| it.is_empty()) {
+                return Err(InvalidSectionError {
+                    section: section.clone(),
+                }
+                .into());
+            }
+        }
+        self.check_bool_values()?;
+        if let Some(inline) = self.inline {
+            if !self.paths.is_empty() {
+                return Err(ConflictingConfigurationError {
+                    keyword: String::from("inline"),
+                    other_keyword: String::from("path"),
+                }
+                .into());
+            }
+            if self.format.is_some() {
+                return Err(ConflictingConfigurationError {
+                    keyword: String::from("inline"),
+                    other_keyword: String::from("format"),
+                }
+                .into());
+            }
+            return Ok(MacroConfig {
+                source: DefinitionSource::Inline(inline),
+                section: self.section,
+                bool_truthy: self.bool_truthy,
+                bool_falsy: self.bool_falsy,
+                file_fallback: self.file_fallback,
+            });
+        }
+        if self.paths.is_empty() {
             return Err(MissingRequiredConfigurationError {
                 keyword: String::from("path"),
             }
             .into());
         }
+        let mut files = Vec::with_capacity(self.paths.len());
+        for path in self.paths {
+            let format = match self.format {
+                Some(f) => f,
+                None => AcceptedFormat::from_path(Path::new(&path))
+                    .ok_or_else(|| UnknownFormatExtensionError::new(path.clone()))?,
+            };
+            files.push(DefinitionFile { path, format });
+        }
         Ok(MacroConfig {
-            path: self
-                .path
-                .as_ref()
-                .expect("path existence is already checked")
-                .clone(),
-            format: self.format.unwrap_or(AcceptedFormat::Hjson),
+            source: DefinitionSource::Files(files),
+            section: self.section,
+            bool_truthy: self.bool_truthy,
+            bool_falsy: self.bool_falsy,
+            file_fallback: self.file_fallback,
         })
     }
+
+    // The sets replace the default ones separately, so a value can be ambiguous also when only
+    // one of them is set.
+    fn check_bool_values(&self) -> Result<(), InvalidBoolValuesError> {
+        for (keyword, values) in [
+            ("bool_truthy", &self.bool_truthy),
+            ("bool_falsy", &self.bool_falsy),
+        ] {
+            if values.as_ref().is_some_and(Vec::is_empty) {
+                return Err(InvalidBoolValuesError {
+                    keyword: keyword.to_string(),
+                    message: String::from("it cannot be empty"),
+                });
+            }
+        }
+        let truthy = variable_declarations::truthy_values(self.bool_truthy.as_deref());
+        let falsy = variable_declarations::falsy_values(self.bool_falsy.as_deref());
+        match variable_declarations::ambiguous_bool_value(&truthy, &falsy) {
+            Some(value) => Err(InvalidBoolValuesError {
+                keyword: String::from(if self.bool_truthy.is_some() {
+                    "bool_truthy"
+                } else {
+                    "bool_falsy"
+                }),
+                message: format!("'{}' is both a truthy and a falsy value", value),
+            }),
+            None => Ok(()),
+        }
+    }
 }
 
 error_node! {
-    pub type MacroConfigBuilderError<MissingRequiredConfigurationError> = "failed to build macro configuration"
+    pub type MacroConfigBuilderError<MissingRequiredConfigurationError, ConflictingConfigurationError, UnknownFormatExtensionError, InvalidSectionError, InvalidBoolValuesError> = "failed to build macro configuration"
 }
 
 #[error_leaf(format!("the required configuration '{}' is missing", self.keyword))]
@@ -104,10 +299,59 @@ pub struct MissingRequiredConfigurationError {
     keyword: String,
 }
 
+#[error_leaf(format!("the configuration '{}' cannot be used together with '{}'", self.keyword, self.other_keyword))]
+pub struct ConflictingConfigurationError {
+    keyword: String,
+    other_keyword: String,
+}
+
+#[error_leaf(format!(
+    "cannot infer the format of '{}', {} or set the 'format' option",
+    self.path,
+    self.hint,
+))]
+pub struct UnknownFormatExtensionError {
+    path: String,
+    hint: String,
+}
+
+impl UnknownFormatExtensionError {
+    pub fn new(path: String) -> Self {
+        let hint = match Path::new(&path)
+            .extension()
+            .and_then(|it| it.to_str())
+            .and_then(AcceptedFormat::disabled_feature)
+        {
+            Some(feature) => format!("enable the `{}` feature to use its extension", feature),
+            None => format!(
+                "use one of the supported extensions ({})",
+                AcceptedFormat::supported_extensions().join(", ")
+            ),
+        };
+        UnknownFormatExtensionError { path, hint }
+    }
+}
+
+#[error_leaf(format!("invalid section '{}', expected a dotted key path (e.g. 'server.http')", self.section))]
+pub struct InvalidSectionError {
+    section: String,
+}
+
+#[error_leaf(format!("invalid '{}': {}", self.keyword, self.message))]
+pub struct InvalidBoolValuesError {
+    keyword: String,
+    message: String,
+}
+
 #[derive(Debug, Clone, Copy)]
 enum MacroConfigKeyword {
     Path,
     Format,
+    Inline,
+    Section,
+    BoolTruthy,
+    BoolFalsy,
+    FileFallback,
 }
 
 impl FromStr for MacroConfigKeyword {
@@ -117,6 +361,11 @@ impl FromStr for MacroConfigKeyword {
         match s {
             "path" => Ok(Self::Path),
             "format" => Ok(Self::Format),
+            "inline" => Ok(Self::Inline),
+            "section" => Ok(Self::Section),
+            "bool_truthy" => Ok(Self::BoolTruthy),
+            "bool_falsy" => Ok(Self::BoolFalsy),
+            "file_fallback" => Ok(Self::FileFallback),
             _ => Err(UnknownOptionError {
                 keyword: s.to_string(),
             }),
@@ -132,6 +381,81 @@ pub struct UnknownOptionError {
 #[derive(Debug, Clone, Copy)]
 pub enum AcceptedFormat {
     Hjson,
+    Dotenv,
+    #[cfg(feature = "toml")]
+    Toml,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "json5")]
+    Json5,
+}
+
+/// The extensions of all the formats, including the ones disabled by the features.
+const FORMAT_EXTENSIONS: [&str; 7] = ["hjson", "env", "toml", "yaml", "yml", "json", "json5"];
+
+impl AcceptedFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name().and_then(|it| it.to_str())?;
+        let extension = path.extension().and_then(|it| it.to_str());
+        if let Some(format) = extension.and_then(Self::from_extension) {
+            return Some(format);
+        }
+        // `.env` and `.env.<suffix>` (e.g. `.env.example`) are dotenv files, unless the suffix is the
+        // extension of a format disabled by the features (e.g. `.env.toml`).
+        let is_dotenv = file_name == ".env"
+            || (file_name.starts_with(".env.")
+                && extension.is_some_and(|it| !FORMAT_EXTENSIONS.contains(&it)));
+        is_dotenv.then_some(Self::Dotenv)
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "hjson" => Some(Self::Hjson),
+            "env" => Some(Self::Dotenv),
+            #[cfg(feature = "toml")]
+            "toml" => Some(Self::Toml),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(Self::Yaml),
+            #[cfg(feature = "json")]
+            "json" => Some(Self::Json),
+            #[cfg(feature = "json5")]
+            "json5" => Some(Self::Json5),
+            _ => None,
+        }
+    }
+
+    pub fn supported_extensions() -> Vec<&'static str> {
+        vec![
+            "hjson",
+            "env",
+            #[cfg(feature = "toml")]
+            "toml",
+            #[cfg(feature = "yaml")]
+            "yaml",
+            #[cfg(feature = "yaml")]
+            "yml",
+            #[cfg(feature = "json")]
+            "json",
+            #[cfg(feature = "json5")]
+            "json5",
+        ]
+    }
+
+    /// The cargo feature to enable for the format with the given name or extension, when the
+    /// format is known but disabled.
+    pub fn disabled_feature(name: &str) -> Option<&'static str> {
+        let extension = FORMAT_EXTENSIONS.iter().find(|it| **it == name)?;
+        if Self::supported_extensions().contains(extension) {
+            return None;
+        }
+        Some(if *extension == "yml" {
+            "yaml"
+        } else {
+            extension
+        })
+    }
 }
 
 impl FromStr for AcceptedFormat {
@@ -140,14 +464,27 @@ impl FromStr for AcceptedFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "hjson" => Ok(Self::Hjson),
+            "dotenv" => Ok(Self::Dotenv),
+            #[cfg(feature = "toml")]
+            "toml" => Ok(Self::Toml),
+            #[cfg(feature = "yaml")]
+            "yaml" => Ok(Self::Yaml),
+            #[cfg(feature = "json")]
+            "json" => Ok(Self::Json),
+            #[cfg(feature = "json5")]
+            "json5" => Ok(Self::Json5),
             _ => Err(InvalidFormatError {
                 format: s.to_string(),
+                hint: Self::disabled_feature(s)
+                    .map(|it| format!(", enable the `{}` feature to use it", it))
+                    .unwrap_or_default(),
             }),
         }
     }
 }
 
-#[error_leaf(format!("invalid format '{}'", self.format))]
+#[error_leaf(format!("invalid format '{}'{}", self.format, self.hint))]
 pub struct InvalidFormatError {
     format: String,
+    hint: String,
 }
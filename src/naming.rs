@@ -0,0 +1,30 @@
+/// The names of the types generated next to the struct, after its name (e.g. `MyConfigUrl`).
+pub const GENERATED_TYPE_SUFFIXES: [&str; 6] = [
+    "HostPort",
+    "Url",
+    "Secret",
+    "RequiredEnvNotFoundError",
+    "InvalidEnvValueError",
+    "EnvFileError",
+];
+
+/// Converts a variable name or an enum value to PascalCase (e.g. `LOG_FORMAT` to `LogFormat`,
+/// `dev-server` to `DevServer`); words are separated by any non-alphanumeric character, and
+/// uppercase words are capitalized (e.g. `HTTP` becomes `Http`).
+pub fn to_pascal_case(s: &str) -> String {
+    s.split(|it: char| !it.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = if word.chars().any(char::is_lowercase) {
+                word.to_string()
+            } else {
+                word.to_lowercase()
+            };
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
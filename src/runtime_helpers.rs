@@ -0,0 +1,68 @@
+// The parsing functions of the types without a suitable `FromStr` implementation are written
+// once, and both compiled in this crate (to validate the default values) and copied in the
+// generated code.
+
+use std::str::FromStr;
+
+use proc_macro2::TokenStream as TokenStream2;
+
+include!("runtime_helpers/byte_size.rs");
+include!("runtime_helpers/duration.rs");
+include!("runtime_helpers/host_port.rs");
+include!("runtime_helpers/url.rs");
+
+const BYTE_SIZE_HELPER: &str = include_str!("runtime_helpers/byte_size.rs");
+const DURATION_HELPER: &str = include_str!("runtime_helpers/duration.rs");
+const HOST_PORT_HELPER: &str = include_str!("runtime_helpers/host_port.rs");
+const URL_HELPER: &str = include_str!("runtime_helpers/url.rs");
+
+/// A parsing function to include in the generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeHelper {
+    ByteSize,
+    Duration,
+    HostPort,
+    Url,
+}
+
+impl RuntimeHelper {
+    /// The name of the function, which takes a `&str` and returns a `Result<T, String>`.
+    pub fn function_name(self) -> TokenStream2 {
+        match self {
+            RuntimeHelper::ByteSize => quote::quote! { parse_byte_size },
+            RuntimeHelper::Duration => quote::quote! { parse_duration },
+            RuntimeHelper::HostPort => quote::quote! { parse_host_port },
+            RuntimeHelper::Url => quote::quote! { parse_url },
+        }
+    }
+
+    /// Parses `value` as done by the generated code, discarding the result.
+    pub fn check(self, value: &str) -> Result<(), String> {
+        match self {
+            RuntimeHelper::ByteSize => parse_byte_size(value).map(drop),
+            RuntimeHelper::Duration => parse_duration(value).map(drop),
+            RuntimeHelper::HostPort => parse_host_port(value).map(drop),
+            RuntimeHelper::Url => parse_url(value).map(drop),
+        }
+    }
+
+    pub fn to_token_stream(self) -> TokenStream2 {
+        let source = match self {
+            RuntimeHelper::ByteSize => BYTE_SIZE_HELPER,
+            RuntimeHelper::Duration => DURATION_HELPER,
+            RuntimeHelper::HostPort => HOST_PORT_HELPER,
+            RuntimeHelper::Url => URL_HELPER,
+        };
+        TokenStream2::from_str(source).expect("runtime helpers are valid Rust code")
+    }
+}
+
+/// The scheme of `value`, parsed as a URL as done by the generated code.
+pub fn url_scheme(value: &str) -> Result<String, String> {
+    parse_url(value).map(|it| it.scheme)
+}
+
+/// `value` without the credentials, if it is a URL.
+pub fn redacted_url(value: &str) -> String {
+    redact_url_credentials(value)
+}
@@ -0,0 +1,36 @@
+// Parses an amount of bytes, optionally followed by a decimal (`kB`, `MB`, `GB`, `TB`, `PB`) or
+// binary (`KiB`, `MiB`, `GiB`, `TiB`, `PiB`) unit (e.g. `512`, `64KiB`, `10MB`).
+fn parse_byte_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let amount_end = value
+        .find(|it: char| !it.is_ascii_digit())
+        .unwrap_or(value.len());
+    if amount_end == 0 {
+        return Err(format!("expected a number at '{}'", value));
+    }
+    let amount: u64 = value[..amount_end]
+        .parse()
+        .map_err(|_| format!("the amount '{}' is too large", &value[..amount_end]))?;
+    let multiplier: u64 = match value[amount_end..].trim_start() {
+        "" | "B" => 1,
+        "kB" | "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "PB" => 1_000_000_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        "PiB" => 1 << 50,
+        unit => {
+            return Err(format!(
+                "unknown unit '{}', expected one of B, kB, MB, GB, TB, PB, KiB, MiB, GiB, TiB, PiB",
+                unit
+            ))
+        }
+    };
+    amount
+        .checked_mul(multiplier)
+        .ok_or_else(|| String::from("the size does not fit in 64 bits"))
+}
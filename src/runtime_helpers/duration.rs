@@ -0,0 +1,56 @@
+// Parses a sequence of amounts followed by their unit (e.g. `1h30m`, `250ms`); the supported units
+// are `ns`, `us`, `ms`, `s`, `m`, `h` and `d`, and `0` is a zero duration.
+fn parse_duration(value: &str) -> Result<std::time::Duration, String> {
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(String::from("the duration is empty"));
+    }
+    // A zero duration is the same in any unit, so the unit can be omitted.
+    if rest == "0" {
+        return Ok(std::time::Duration::ZERO);
+    }
+    let mut duration = std::time::Duration::ZERO;
+    while !rest.is_empty() {
+        let amount_end = rest
+            .find(|it: char| !it.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if amount_end == 0 {
+            return Err(format!("expected a number at '{}'", rest));
+        }
+        let amount: u64 = rest[..amount_end]
+            .parse()
+            .map_err(|_| format!("the amount '{}' is too large", &rest[..amount_end]))?;
+        rest = &rest[amount_end..];
+        let unit_end = rest
+            .find(|it: char| it.is_ascii_digit() || it.is_whitespace())
+            .unwrap_or(rest.len());
+        let component = match &rest[..unit_end] {
+            "ns" => Some(std::time::Duration::from_nanos(amount)),
+            "us" => Some(std::time::Duration::from_micros(amount)),
+            "ms" => Some(std::time::Duration::from_millis(amount)),
+            "s" => Some(std::time::Duration::from_secs(amount)),
+            "m" => amount.checked_mul(60).map(std::time::Duration::from_secs),
+            "h" => amount.checked_mul(3600).map(std::time::Duration::from_secs),
+            "d" => amount
+                .checked_mul(86400)
+                .map(std::time::Duration::from_secs),
+            "" => {
+                return Err(format!(
+                    "missing unit after '{}', expected one of ns, us, ms, s, m, h, d",
+                    amount
+                ))
+            }
+            unit => {
+                return Err(format!(
+                    "unknown unit '{}', expected one of ns, us, ms, s, m, h, d",
+                    unit
+                ))
+            }
+        };
+        duration = component
+            .and_then(|it| duration.checked_add(it))
+            .ok_or_else(|| String::from("the duration is too large"))?;
+        rest = rest[unit_end..].trim_start();
+    }
+    Ok(duration)
+}
@@ -0,0 +1,41 @@
+// Parses a `host:port` pair, where the host is a DNS name, an IPv4 address or an IPv6 address in
+// square brackets (e.g. `localhost:8080`, `10.0.0.1:5432`, `[::1]:8080`).
+fn parse_host_port(value: &str) -> Result<(String, u16), String> {
+    let expected = "expected 'host:port', with a DNS name or an IP address as host and a number \
+        between 0 and 65535 as port (e.g. 'localhost:8080' or '[::1]:8080')";
+    let (host, port) = value
+        .rsplit_once(':')
+        .ok_or_else(|| String::from(expected))?;
+    let port: u16 = port.parse().map_err(|_| String::from(expected))?;
+    let host = match host.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+        Some(ipv6) => {
+            ipv6.parse::<std::net::Ipv6Addr>()
+                .map_err(|_| format!("invalid IPv6 address '{}', {}", ipv6, expected))?;
+            ipv6
+        }
+        None => {
+            // Underscores are not allowed by RFC 1123, but they are common in the names of
+            // containers (e.g. `kafka_1`); a numeric host must be a valid IPv4 address.
+            let is_numeric = host.chars().all(|it| it.is_ascii_digit() || it == '.');
+            let is_valid_host = if is_numeric {
+                host.parse::<std::net::Ipv4Addr>().is_ok()
+            } else {
+                host.len() <= 253
+                    && host.split('.').all(|label| {
+                        !label.is_empty()
+                            && label.len() <= 63
+                            && !label.starts_with('-')
+                            && !label.ends_with('-')
+                            && label
+                                .chars()
+                                .all(|it| it.is_ascii_alphanumeric() || it == '-' || it == '_')
+                    })
+            };
+            if !is_valid_host {
+                return Err(format!("invalid host '{}', {}", host, expected));
+            }
+            host
+        }
+    };
+    Ok((host.to_string(), port))
+}
@@ -0,0 +1,132 @@
+// The components of an absolute URL with an authority (e.g. `postgres://user:pass@db:5432/app`);
+// the scheme is in lowercase, and `redacted` is the URL without the credentials. The macro only
+// reads the scheme, hence the `allow`.
+#[allow(dead_code)]
+struct UrlParts {
+    scheme: String,
+    username: String,
+    password: Option<String>,
+    host: String,
+    port: Option<u16>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+    redacted: String,
+}
+
+fn parse_url(value: &str) -> Result<UrlParts, String> {
+    let expected = "expected an absolute URL with an authority (e.g. 'https://example.com/path')";
+    if value
+        .chars()
+        .any(|it| it.is_whitespace() || it.is_control())
+    {
+        return Err(format!("the URL cannot contain whitespaces, {}", expected));
+    }
+    let (scheme, rest) = value
+        .split_once("://")
+        .ok_or_else(|| format!("missing '://' after the scheme, {}", expected))?;
+    let is_valid_scheme = scheme.starts_with(|it: char| it.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|it| it.is_ascii_alphanumeric() || it == '+' || it == '-' || it == '.');
+    if !is_valid_scheme {
+        return Err(format!("invalid scheme, {}", expected));
+    }
+    // The errors do not show the pieces of the value, which can be parts of the credentials when
+    // they contain the delimiters of the authority.
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(authority_end);
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((userinfo, host_port)) => (Some(userinfo), host_port),
+        None => (None, authority),
+    };
+    let (username, password) = match userinfo {
+        Some(userinfo) => match userinfo.split_once(':') {
+            Some((username, password)) => (username, Some(password.to_string())),
+            None => (userinfo, None),
+        },
+        None => ("", None),
+    };
+    let redacted = match (userinfo, &password) {
+        (Some(_), Some(_)) => format!("{}://{}:[REDACTED]@{}{}", scheme, username, host_port, rest),
+        (Some(_), None) => format!("{}://[REDACTED]@{}{}", scheme, host_port, rest),
+        (None, _) => value.to_string(),
+    };
+    let (host, port) = if let Some(bracketed) = host_port.strip_prefix('[') {
+        let (ipv6, port) = bracketed
+            .split_once(']')
+            .ok_or_else(|| format!("unclosed '[' in the host, {}", expected))?;
+        ipv6.parse::<std::net::Ipv6Addr>()
+            .map_err(|_| format!("invalid IPv6 address in the host, {}", expected))?;
+        (&host_port[..ipv6.len() + 2], port)
+    } else {
+        match host_port.find(':') {
+            Some(index) => host_port.split_at(index),
+            None => (host_port, ""),
+        }
+    };
+    if host.is_empty() {
+        return Err(format!("missing host, {}", expected));
+    }
+    let is_valid_host = host.starts_with('[')
+        || host
+            .chars()
+            .all(|it| it.is_ascii_alphanumeric() || "-._~%".contains(it));
+    if !is_valid_host {
+        return Err(format!("invalid host, {}", expected));
+    }
+    let port = match port.strip_prefix(':') {
+        Some(port) => Some(
+            port.parse::<u16>()
+                .map_err(|_| format!("invalid port, {}", expected))?,
+        ),
+        None if port.is_empty() => None,
+        None => {
+            return Err(format!(
+                "unexpected characters after the host, {}",
+                expected
+            ))
+        }
+    };
+    let (rest, fragment) = match rest.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment.to_string())),
+        None => (rest, None),
+    };
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, Some(query.to_string())),
+        None => (rest, None),
+    };
+    Ok(UrlParts {
+        scheme: scheme.to_ascii_lowercase(),
+        username: username.to_string(),
+        password,
+        host: host.to_string(),
+        port,
+        path: path.to_string(),
+        query,
+        fragment,
+        redacted,
+    })
+}
+
+// Replaces the password (or the whole user information, when it has no password, since it can
+// be a token) of the URL with `[REDACTED]`. The value can be an invalid URL with credentials
+// containing `/`, `?` or `#`, so everything up to the last `@` is considered user information.
+fn redact_url_credentials(value: &str) -> String {
+    let (scheme, rest) = match value.split_once("://") {
+        Some((scheme, rest)) if !scheme.contains('@') => (format!("{}://", scheme), rest),
+        _ => (String::new(), value),
+    };
+    match rest.rsplit_once('@') {
+        Some((userinfo, host_port)) => {
+            let userinfo = match userinfo.split_once(':') {
+                Some((username, _password)) if !username.contains(['/', '?', '#']) => {
+                    format!("{}:[REDACTED]", username)
+                }
+                _ => String::from("[REDACTED]"),
+            };
+            format!("{}{}@{}", scheme, userinfo, host_port)
+        }
+        None => value.to_string(),
+    }
+}
@@ -1,15 +1,92 @@
-use std::ops::Deref;
+use std::{error::Error, ops::Deref, path::Path};
 
-use serde::{de::Visitor, Deserialize};
+use hierrorchy::{error_leaf, error_node};
+use proc_macro2::Span;
+use serde::{
+    de::{
+        value::MapAccessDeserializer, DeserializeSeed, Error as _, IgnoredAny, IntoDeserializer,
+        Visitor,
+    },
+    Deserialize,
+};
+use syn::Error as SynError;
 
-use crate::accepted_rust_type::AcceptedRustType;
+use crate::{
+    accepted_rust_type::AcceptedRustType,
+    naming::{to_pascal_case, GENERATED_TYPE_SUFFIXES},
+    runtime_helpers,
+};
+
+const INCLUDE_DIRECTIVE: &str = "$include";
+const EXTENDS_DIRECTIVE: &str = "$extends";
+const DEFAULT_SEPARATOR: &str = ",";
+// The fields of `VariableConfiguration`, as named in the definition files.
+const VARIABLE_FIELDS: &[&str] = &[
+    "type",
+    "description",
+    "default",
+    "required",
+    "separator",
+    "trim",
+    "values",
+    "case_insensitive",
+    "expand_tilde",
+    "base_dir",
+    "schemes",
+    "parser",
+    "truthy",
+    "falsy",
+    "secret",
+    "file_fallback",
+];
+const DEFAULT_TRUTHY: [&str; 4] = ["true", "1", "yes", "on"];
+const DEFAULT_FALSY: [&str; 4] = ["false", "0", "no", "off"];
+
+/// The values read as `true`, which are the default ones unless `values` is set.
+pub fn truthy_values(values: Option<&[String]>) -> Vec<&str> {
+    match values {
+        Some(values) => values.iter().map(String::as_str).collect(),
+        None => DEFAULT_TRUTHY.to_vec(),
+    }
+}
+
+/// The values read as `false`, which are the default ones unless `values` is set.
+pub fn falsy_values(values: Option<&[String]>) -> Vec<&str> {
+    match values {
+        Some(values) => values.iter().map(String::as_str).collect(),
+        None => DEFAULT_FALSY.to_vec(),
+    }
+}
+
+/// A value which is both truthy and falsy; bool values are matched ignoring the ASCII case.
+pub fn ambiguous_bool_value(truthy: &[&str], falsy: &[&str]) -> Option<String> {
+    truthy
+        .iter()
+        .find(|value| falsy.iter().any(|it| it.eq_ignore_ascii_case(value)))
+        .map(|value| value.to_string())
+}
 
 #[derive(Debug)]
 pub struct EnvVariableSpec {
     name: String,
-    rust_type: AcceptedRustType,
-    description: String,
+    rust_type: Option<AcceptedRustType>,
+    description: Option<String>,
     default_value: Option<String>,
+    required: Option<bool>,
+    separator: Option<String>,
+    trim: Option<bool>,
+    values: Option<Vec<String>>,
+    case_insensitive: Option<bool>,
+    expand_tilde: Option<bool>,
+    base_dir: Option<String>,
+    schemes: Option<Vec<String>>,
+    parser: Option<String>,
+    truthy: Option<Vec<String>>,
+    falsy: Option<Vec<String>>,
+    secret: Option<bool>,
+    file_fallback: Option<bool>,
+    // The position of the declaration in an inline definition, where its errors are reported.
+    span: Option<Span>,
 }
 
 impl EnvVariableSpec {
@@ -19,6 +96,20 @@ impl EnvVariableSpec {
             rust_type: fields.rust_type,
             description: fields.description,
             default_value: fields.default_value,
+            required: fields.required,
+            separator: fields.separator,
+            trim: fields.trim,
+            values: fields.values,
+            case_insensitive: fields.case_insensitive,
+            expand_tilde: fields.expand_tilde,
+            base_dir: fields.base_dir,
+            schemes: fields.schemes,
+            parser: fields.parser,
+            truthy: fields.truthy,
+            falsy: fields.falsy,
+            secret: fields.secret,
+            file_fallback: fields.file_fallback,
+            span: None,
         }
     }
 
@@ -26,23 +117,638 @@ impl EnvVariableSpec {
         &self.name
     }
 
-    pub fn rust_type(&self) -> AcceptedRustType {
+    pub fn rust_type(&self) -> &AcceptedRustType {
         self.rust_type
+            .as_ref()
+            .expect("variable declarations are validated before use")
     }
 
     #[allow(dead_code)]
     pub fn description(&self) -> &str {
-        &self.description
+        self.description
+            .as_deref()
+            .expect("variable declarations are validated before use")
     }
 
     pub fn default_value(&self) -> Option<&str> {
         self.default_value.as_deref()
     }
+
+    /// Whether the variable must be set (or have a default value); an optional variable
+    /// generates an `Option` field.
+    pub fn is_required(&self) -> bool {
+        self.required.unwrap_or(true)
+    }
+
+    /// The separator of the elements of a list variable.
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(DEFAULT_SEPARATOR)
+    }
+
+    /// Whether the whitespaces around the elements of a list variable are removed.
+    pub fn trim(&self) -> bool {
+        self.trim.unwrap_or(false)
+    }
+
+    /// The accepted values of an enum variable.
+    pub fn values(&self) -> &[String] {
+        self.values.as_deref().unwrap_or_default()
+    }
+
+    /// Whether a leading `~` in a path variable is expanded to the home directory.
+    pub fn expand_tilde(&self) -> bool {
+        self.expand_tilde.unwrap_or(false)
+    }
+
+    /// The directory the relative paths of a path variable are resolved against.
+    pub fn base_dir(&self) -> Option<&str> {
+        self.base_dir.as_deref()
+    }
+
+    /// The error message for a value of an enum variable which is not one of its values.
+    pub fn expected_values_message(&self) -> String {
+        format!(
+            "expected one of {}",
+            self.values()
+                .iter()
+                .map(|value| format!("'{}'", value))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    /// The schemes accepted by a URL variable, when restricted.
+    pub fn schemes(&self) -> Option<&[String]> {
+        self.schemes.as_deref()
+    }
+
+    /// The error message for a URL with a scheme which is not accepted by the variable.
+    pub fn expected_schemes_message(&self) -> String {
+        format!(
+            "expected one of {}",
+            self.schemes()
+                .unwrap_or_default()
+                .iter()
+                .map(|scheme| format!("'{}'", scheme))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    /// The path of the function parsing the value (or each element of a list) of the variable,
+    /// instead of `FromStr`.
+    pub fn parser(&self) -> Option<&str> {
+        self.parser.as_deref()
+    }
+
+    /// Whether the value of the variable is wrapped in a type which does not show it.
+    pub fn is_secret(&self) -> bool {
+        self.secret.unwrap_or(false)
+    }
+
+    /// Whether the value can be read from the file in the variable `<NAME>_FILE` instead.
+    pub fn file_fallback(&self) -> bool {
+        self.file_fallback.unwrap_or(false)
+    }
+
+    /// The values read as `true` by a bool variable, ignoring the ASCII case.
+    pub fn truthy(&self) -> Vec<&str> {
+        truthy_values(self.truthy.as_deref())
+    }
+
+    /// The values read as `false` by a bool variable, ignoring the ASCII case.
+    pub fn falsy(&self) -> Vec<&str> {
+        falsy_values(self.falsy.as_deref())
+    }
+
+    /// The error message for a value of a bool variable which is neither truthy nor falsy.
+    pub fn expected_bool_message(&self) -> String {
+        let quoted = |values: Vec<&str>| {
+            values
+                .iter()
+                .map(|value| format!("'{}'", value))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        format!(
+            "expected one of {} for true or one of {} for false",
+            quoted(self.truthy()),
+            quoted(self.falsy())
+        )
+    }
+
+    /// Whether the value of an enum variable is matched ignoring the ASCII case.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive.unwrap_or(false)
+    }
+
+    fn override_with(&mut self, other: EnvVariableSpec) {
+        if other.rust_type.is_some() {
+            self.rust_type = other.rust_type;
+        }
+        if other.description.is_some() {
+            self.description = other.description;
+        }
+        if other.default_value.is_some() {
+            self.default_value = other.default_value;
+        }
+        if other.required.is_some() {
+            self.required = other.required;
+        }
+        if other.separator.is_some() {
+            self.separator = other.separator;
+        }
+        if other.trim.is_some() {
+            self.trim = other.trim;
+        }
+        if other.values.is_some() {
+            self.values = other.values;
+        }
+        if other.case_insensitive.is_some() {
+            self.case_insensitive = other.case_insensitive;
+        }
+        if other.expand_tilde.is_some() {
+            self.expand_tilde = other.expand_tilde;
+        }
+        if other.base_dir.is_some() {
+            self.base_dir = other.base_dir;
+        }
+        if other.schemes.is_some() {
+            self.schemes = other.schemes;
+        }
+        if other.parser.is_some() {
+            self.parser = other.parser;
+        }
+        if other.truthy.is_some() {
+            self.truthy = other.truthy;
+        }
+        if other.falsy.is_some() {
+            self.falsy = other.falsy;
+        }
+        if other.secret.is_some() {
+            self.secret = other.secret;
+        }
+        if other.file_fallback.is_some() {
+            self.file_fallback = other.file_fallback;
+        }
+        if other.span.is_some() {
+            self.span = other.span;
+        }
+    }
+
+    fn validate(&self) -> Result<(), DeclarationValidationError> {
+        let missing_field = if self.rust_type.is_none() {
+            Some("type")
+        } else if self.description.is_none() {
+            Some("description")
+        } else if matches!(self.rust_type, Some(AcceptedRustType::Enum)) && self.values.is_none() {
+            Some("values")
+        } else {
+            None
+        };
+        if let Some(field) = missing_field {
+            return Err(IncompleteVariableError {
+                name: self.name.clone(),
+                field: field.to_string(),
+            }
+            .into());
+        }
+        if !self.is_required() && self.default_value.is_some() {
+            return Err(OptionalVariableWithDefaultError {
+                name: self.name.clone(),
+            }
+            .into());
+        }
+        let is_list = matches!(self.rust_type, Some(AcceptedRustType::Vec(_)));
+        let is_enum = matches!(self.rust_type, Some(AcceptedRustType::Enum));
+        self.check_field_type("separator", self.separator.is_some(), is_list, "list types")?;
+        self.check_field_type("trim", self.trim.is_some(), is_list, "list types")?;
+        self.check_field_type("values", self.values.is_some(), is_enum, "enum types")?;
+        self.check_field_type(
+            "case_insensitive",
+            self.case_insensitive.is_some(),
+            is_enum,
+            "enum types",
+        )?;
+        let is_path = matches!(self.rust_type, Some(AcceptedRustType::PathBuf));
+        self.check_field_type(
+            "expand_tilde",
+            self.expand_tilde.is_some(),
+            is_path,
+            "path types",
+        )?;
+        self.check_field_type("base_dir", self.base_dir.is_some(), is_path, "path types")?;
+        let is_url = matches!(
+            self.rust_type.as_ref().map(AcceptedRustType::element_type),
+            Some(AcceptedRustType::Url)
+        );
+        self.check_field_type("schemes", self.schemes.is_some(), is_url, "URL types")?;
+        // The types generated by the macro cannot be built by a parser, and paths are not read
+        // as strings.
+        let is_parsable = !matches!(
+            self.rust_type.as_ref().map(AcceptedRustType::element_type),
+            Some(
+                AcceptedRustType::Enum
+                    | AcceptedRustType::PathBuf
+                    | AcceptedRustType::HostPort
+                    | AcceptedRustType::Url
+            )
+        );
+        self.check_field_type(
+            "parser",
+            self.parser.is_some(),
+            is_parsable,
+            "types other than enum, PathBuf, HostPort and Url",
+        )?;
+        if let Some(parser) = &self.parser {
+            if syn::parse_str::<syn::Path>(parser).is_err() {
+                return Err(self.invalid_field(
+                    "parser",
+                    format!("'{}' is not the path of a function", parser),
+                ));
+            }
+        }
+        let is_bool = matches!(
+            self.rust_type.as_ref().map(AcceptedRustType::element_type),
+            Some(AcceptedRustType::bool)
+        );
+        self.check_field_type("truthy", self.truthy.is_some(), is_bool, "bool types")?;
+        self.check_field_type("falsy", self.falsy.is_some(), is_bool, "bool types")?;
+        if self.truthy.as_ref().is_some_and(Vec::is_empty) {
+            return Err(self.invalid_field("truthy", String::from("it cannot be empty")));
+        }
+        if self.falsy.as_ref().is_some_and(Vec::is_empty) {
+            return Err(self.invalid_field("falsy", String::from("it cannot be empty")));
+        }
+        if is_bool {
+            if let Some(value) = ambiguous_bool_value(&self.truthy(), &self.falsy()) {
+                return Err(AmbiguousBoolValueError {
+                    name: self.name.clone(),
+                    value,
+                }
+                .into());
+            }
+        }
+        let is_string = matches!(self.rust_type, Some(AcceptedRustType::String));
+        self.check_field_type("secret", self.secret.is_some(), is_string, "String types")?;
+        // The errors of a parser could show the value.
+        if self.is_secret() && self.parser.is_some() {
+            return Err(self.invalid_field(
+                "secret",
+                String::from("a secret variable cannot have a parser"),
+            ));
+        }
+        if self.schemes.as_ref().is_some_and(Vec::is_empty) {
+            return Err(self.invalid_field("schemes", String::from("it cannot be empty")));
+        }
+        if self.separator.as_deref() == Some("") {
+            return Err(self.invalid_field("separator", String::from("it cannot be empty")));
+        }
+        if let Some(values) = &self.values {
+            self.check_enum_values(values)?;
+        }
+        if self
+            .base_dir
+            .as_deref()
+            .is_some_and(|it| !Path::new(it).is_absolute())
+        {
+            return Err(self.invalid_field("base_dir", String::from("it must be an absolute path")));
+        }
+        // The parsers are only known by the compiler, so the default value is checked when the
+        // configuration is loaded.
+        if let (Some(default_value), None) = (&self.default_value, &self.parser) {
+            self.check_value(default_value)
+                .map_err(|message| InvalidDefaultValueError {
+                    name: self.name.clone(),
+                    value: if is_url {
+                        runtime_helpers::redacted_url(default_value)
+                    } else {
+                        default_value.clone()
+                    },
+                    message,
+                })?;
+        }
+        Ok(())
+    }
+
+    // Default values are checked when the macro is expanded, with the rules of the generated code.
+    fn check_value(&self, value: &str) -> Result<(), String> {
+        match self.rust_type() {
+            AcceptedRustType::Vec(element_type) => {
+                if value.is_empty() {
+                    return Ok(());
+                }
+                for (index, item) in value.split(self.separator()).enumerate() {
+                    let item = if self.trim() { item.trim() } else { item };
+                    self.check_element(element_type, item)
+                        .map_err(|it| format!("element {}: {}", index, it))?;
+                }
+                Ok(())
+            }
+            AcceptedRustType::Enum => {
+                let is_accepted = self.values().iter().any(|it| {
+                    if self.is_case_insensitive() {
+                        it.eq_ignore_ascii_case(value)
+                    } else {
+                        it == value
+                    }
+                });
+                if is_accepted {
+                    Ok(())
+                } else {
+                    Err(self.expected_values_message())
+                }
+            }
+            rust_type => self.check_element(rust_type, value),
+        }
+    }
+
+    fn check_element(&self, rust_type: &AcceptedRustType, value: &str) -> Result<(), String> {
+        if let AcceptedRustType::bool = rust_type {
+            let is_accepted = self
+                .truthy()
+                .iter()
+                .chain(self.falsy().iter())
+                .any(|it| it.eq_ignore_ascii_case(value));
+            return if is_accepted {
+                Ok(())
+            } else {
+                Err(self.expected_bool_message())
+            };
+        }
+        rust_type.check_value(value)?;
+        if let (AcceptedRustType::Url, Some(schemes)) = (rust_type, &self.schemes) {
+            let scheme = runtime_helpers::url_scheme(value)?;
+            if !schemes.iter().any(|it| it.eq_ignore_ascii_case(&scheme)) {
+                return Err(format!(
+                    "unsupported scheme '{}', {}",
+                    scheme,
+                    self.expected_schemes_message()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_field_type(
+        &self,
+        field: &str,
+        is_set: bool,
+        is_valid_type: bool,
+        expected: &str,
+    ) -> Result<(), DeclarationValidationError> {
+        if is_set && !is_valid_type {
+            Err(MisplacedVariableFieldError {
+                name: self.name.clone(),
+                field: field.to_string(),
+                expected: expected.to_string(),
+            }
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Each value becomes a variant of the generated enum, so it must be convertible to a Rust
+    // identifier, different from the other ones.
+    fn check_enum_values(&self, values: &[String]) -> Result<(), DeclarationValidationError> {
+        if values.is_empty() {
+            return Err(self.invalid_field("values", String::from("it cannot be empty")));
+        }
+        for (index, value) in values.iter().enumerate() {
+            if syn::parse_str::<syn::Ident>(&to_pascal_case(value)).is_err() {
+                return Err(self.invalid_field(
+                    "values",
+                    format!("'{}' cannot be converted to an enum variant", value),
+                ));
+            }
+            let duplicate = values[..index].iter().find(|other| {
+                to_pascal_case(other) == to_pascal_case(value)
+                    || (self.is_case_insensitive() && other.eq_ignore_ascii_case(value))
+            });
+            if let Some(other) = duplicate {
+                return Err(self.invalid_field(
+                    "values",
+                    format!("'{}' and '{}' cannot be told apart", other, value),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The enum of the variable is named after the struct and the variable (e.g. `MyConfigLogLevel`),
+    // so it must differ from the other types generated for the struct.
+    fn check_enum_name<'a>(
+        &'a self,
+        enum_names: &mut Vec<(String, &'a str)>,
+    ) -> Result<(), DeclarationValidationError> {
+        if !matches!(
+            self.rust_type.as_ref().map(AcceptedRustType::element_type),
+            Some(AcceptedRustType::Enum)
+        ) {
+            return Ok(());
+        }
+        let enum_name = to_pascal_case(&self.name);
+        let conflict = if GENERATED_TYPE_SUFFIXES.contains(&enum_name.as_str()) {
+            Some(format!("the generated '{}' type", enum_name))
+        } else {
+            enum_names
+                .iter()
+                .find(|it| it.0 == enum_name)
+                .map(|it| format!("the enum of variable '{}'", it.1))
+        };
+        if let Some(conflict) = conflict {
+            return Err(EnumNameConflictError {
+                name: self.name.clone(),
+                conflict,
+            }
+            .into());
+        }
+        enum_names.push((enum_name, &self.name));
+        Ok(())
+    }
+
+    fn invalid_field(&self, field: &str, message: String) -> DeclarationValidationError {
+        InvalidVariableFieldError {
+            name: self.name.clone(),
+            field: field.to_string(),
+            message,
+        }
+        .into()
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct EnvVariableDeclarations {
     declarations: Vec<EnvVariableSpec>,
+    includes: Vec<String>,
+    sections: Vec<(String, EnvVariableDeclarations)>,
+}
+
+impl EnvVariableDeclarations {
+    pub fn extend(&mut self, other: EnvVariableDeclarations) {
+        self.declarations.extend(other.declarations);
+    }
+
+    /// Adds the declarations of `other`; the variables already declared are updated with the
+    /// fields specified in `other`, instead of being duplicated.
+    pub fn override_with(&mut self, other: EnvVariableDeclarations) {
+        for spec in other.declarations {
+            match self.declarations.iter_mut().find(|it| it.name == spec.name) {
+                Some(existing) => existing.override_with(spec),
+                None => self.declarations.push(spec),
+            }
+        }
+    }
+
+    /// The files included by the `$include` and `$extends` directives, in declaration order.
+    pub fn take_includes(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.includes)
+    }
+
+    /// Extracts the nested section addressed by a dotted key path (e.g. `database.pool`).
+    pub fn take_section(&mut self, section: &str) -> Option<EnvVariableDeclarations> {
+        let (name, subsection) = match section.split_once('.') {
+            Some((name, subsection)) => (name, Some(subsection)),
+            None => (section, None),
+        };
+        let index = self.sections.iter().position(|it| it.0 == name)?;
+        let mut declarations = self.sections.swap_remove(index).1;
+        match subsection {
+            Some(subsection) => declarations.take_section(subsection),
+            None => Some(declarations),
+        }
+    }
+
+    /// Sets the truthy and falsy values of the bool variables which do not set their own ones.
+    pub fn set_default_bool_values(&mut self, truthy: Option<&[String]>, falsy: Option<&[String]>) {
+        for it in &mut self.declarations {
+            if !matches!(
+                it.rust_type.as_ref().map(AcceptedRustType::element_type),
+                Some(AcceptedRustType::bool)
+            ) {
+                continue;
+            }
+            if it.truthy.is_none() {
+                it.truthy = truthy.map(<[String]>::to_vec);
+            }
+            if it.falsy.is_none() {
+                it.falsy = falsy.map(<[String]>::to_vec);
+            }
+        }
+    }
+
+    /// Sets the file fallback of the variables which do not set their own one.
+    pub fn set_default_file_fallback(&mut self, file_fallback: bool) {
+        for it in &mut self.declarations {
+            it.file_fallback = it.file_fallback.or(Some(file_fallback));
+        }
+    }
+
+    /// The nested section with the given name, if any.
+    pub fn section_mut(&mut self, name: &str) -> Option<&mut EnvVariableDeclarations> {
+        self.sections
+            .iter_mut()
+            .find(|it| it.0 == name)
+            .map(|it| &mut it.1)
+    }
+
+    /// Sets the position of the declaration of the variable with the given name, if any.
+    pub fn set_span(&mut self, name: &str, span: Span) {
+        if let Some(spec) = self.declarations.iter_mut().find(|it| it.name == name) {
+            spec.span = Some(span);
+        }
+    }
+
+    /// Validates the declarations, reporting the errors at their position in an inline definition
+    /// (or at the macro call site for the definition files).
+    pub fn validate(&self) -> Result<(), SynError> {
+        let mut enum_names: Vec<(String, &str)> = Vec::new();
+        for it in &self.declarations {
+            it.validate()
+                .and_then(|_| it.check_enum_name(&mut enum_names))
+                .map_err(|error| SynError::new(it.span.unwrap_or_else(Span::call_site), error))?;
+        }
+        Ok(())
+    }
+}
+
+error_node! {
+    pub type DeclarationValidationError<IncompleteVariableError, OptionalVariableWithDefaultError, MisplacedVariableFieldError, InvalidVariableFieldError, AmbiguousBoolValueError, InvalidDefaultValueError, EnumNameConflictError> = "invalid variable declaration"
+}
+
+#[error_leaf(format!(
+    "variable '{}' has no {}, specify it in its declaration or in an included file",
+    self.name,
+    self.field,
+))]
+pub struct IncompleteVariableError {
+    name: String,
+    field: String,
+}
+
+#[error_leaf(format!(
+    "variable '{}' is not required but has a default value, remove one of them",
+    self.name,
+))]
+pub struct OptionalVariableWithDefaultError {
+    name: String,
+}
+
+#[error_leaf(format!(
+    "variable '{}' has the field '{}', which is only valid for {}",
+    self.name,
+    self.field,
+    self.expected,
+))]
+pub struct MisplacedVariableFieldError {
+    name: String,
+    field: String,
+    expected: String,
+}
+
+#[error_leaf(format!(
+    "invalid field '{}' of variable '{}': {}",
+    self.field,
+    self.name,
+    self.message,
+))]
+pub struct InvalidVariableFieldError {
+    name: String,
+    field: String,
+    message: String,
+}
+
+#[error_leaf(format!(
+    "variable '{}' reads '{}' both as true and as false, remove it from 'truthy' or 'falsy'",
+    self.name,
+    self.value,
+))]
+pub struct AmbiguousBoolValueError {
+    name: String,
+    value: String,
+}
+
+#[error_leaf(format!(
+    "the enum of variable '{}' has the same name as {}, rename the variable",
+    self.name,
+    self.conflict,
+))]
+pub struct EnumNameConflictError {
+    name: String,
+    conflict: String,
+}
+
+#[error_leaf(format!(
+    "invalid default value '{}' for variable '{}': {}",
+    self.value,
+    self.name,
+    self.message,
+))]
+pub struct InvalidDefaultValueError {
+    name: String,
+    value: String,
+    message: String,
 }
 
 impl Deref for EnvVariableDeclarations {
@@ -64,13 +770,7 @@ impl<'de> Deserialize<'de> for EnvVariableDeclarations {
     where
         D: serde::Deserializer<'de>,
     {
-        Ok(EnvVariableDeclarations {
-            declarations: deserializer
-                .deserialize_map(VariableDeclarationVisitor::new())?
-                .into_iter()
-                .map(|it| EnvVariableSpec::from_name_and_fields(it.0, it.1))
-                .collect(),
-        })
+        deserializer.deserialize_map(VariableDeclarationVisitor::new())
     }
 }
 
@@ -83,7 +783,7 @@ impl VariableDeclarationVisitor {
 }
 
 impl<'de> Visitor<'de> for VariableDeclarationVisitor {
-    type Value = Vec<(String, VariableConfiguration)>;
+    type Value = EnvVariableDeclarations;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
         formatter.write_str("a map")
@@ -93,31 +793,413 @@ impl<'de> Visitor<'de> for VariableDeclarationVisitor {
     where
         E: serde::de::Error,
     {
-        Ok(Vec::new())
+        Ok(EnvVariableDeclarations::default())
     }
 
     fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
     where
         A: serde::de::MapAccess<'de>,
     {
-        let mut values = Vec::with_capacity(core::cmp::min(map.size_hint().unwrap_or(0), 128));
+        let mut result = EnvVariableDeclarations {
+            declarations: Vec::with_capacity(core::cmp::min(map.size_hint().unwrap_or(0), 128)),
+            ..Default::default()
+        };
+
+        while let Some(key) = map.next_key::<String>()? {
+            if key.starts_with('$') {
+                result.add_directive(&key, &mut map)?;
+            } else {
+                let entry = map.next_value::<DeclarationEntry>()?;
+                result.add_entry(key, entry);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl EnvVariableDeclarations {
+    fn add_directive<'de, A>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        match key {
+            INCLUDE_DIRECTIVE => self
+                .includes
+                .extend(map.next_value::<IncludeDirective>()?.0),
+            EXTENDS_DIRECTIVE => self.includes.push(map.next_value::<String>()?),
+            _ => {
+                return Err(A::Error::custom(format!(
+                    "unknown directive '{}', expected '{}' or '{}'",
+                    key, INCLUDE_DIRECTIVE, EXTENDS_DIRECTIVE
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn add_entry(&mut self, key: String, entry: DeclarationEntry) {
+        match entry {
+            DeclarationEntry::Variable(fields) => self
+                .declarations
+                .push(EnvVariableSpec::from_name_and_fields(key, fields)),
+            DeclarationEntry::Section(section) => self.sections.push((key, section)),
+        }
+    }
+}
+
+// A value of the declaration map is either the configuration of a variable or a nested section
+// (a map of declarations); a map is a section when none of its keys is a variable configuration
+// field, otherwise its keys which are not fields are reported as unknown fields.
+enum DeclarationEntry {
+    Variable(VariableConfiguration),
+    Section(EnvVariableDeclarations),
+}
+
+impl<'de> Deserialize<'de> for DeclarationEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(DeclarationEntryVisitor {})
+    }
+}
+
+struct DeclarationEntryVisitor {}
+
+impl<'de> Visitor<'de> for DeclarationEntryVisitor {
+    type Value = DeclarationEntry;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a variable declaration or a section")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut keys = EntryKeys::default();
+        let mut section = EnvVariableDeclarations::default();
+        let fields =
+            VariableConfiguration::deserialize(MapAccessDeserializer::new(FieldsMapAccess {
+                map,
+                keys: &mut keys,
+                section: &mut section,
+            }))?;
+        let unexpected_key = match keys.has_fields {
+            true => keys.first_unknown.as_ref().or(keys.first_nested.as_ref()),
+            false => keys.first_unknown.as_ref(),
+        };
+        if let Some(key) = unexpected_key {
+            Err(A::Error::unknown_field(key, VARIABLE_FIELDS))
+        } else if keys.first_nested.is_some() {
+            Ok(DeclarationEntry::Section(section))
+        } else {
+            // An empty map is an incomplete variable, rather than an empty section.
+            Ok(DeclarationEntry::Variable(fields))
+        }
+    }
+}
+
+#[derive(Default)]
+struct EntryKeys {
+    has_fields: bool,
+    // The first key which is not a field and has a scalar or list value.
+    first_unknown: Option<String>,
+    // The first key which is a directive or has a map value, as the entries of a section.
+    first_nested: Option<String>,
+}
 
-        while let Some((key, value)) = map.next_entry()? {
-            values.push((key, value));
+// Yields the variable configuration fields of the map, collecting its other keys into a section;
+// once a section entry is found, the keys named as fields are section entries as well.
+struct FieldsMapAccess<'a, A> {
+    map: A,
+    keys: &'a mut EntryKeys,
+    section: &'a mut EnvVariableDeclarations,
+}
+
+impl<'de, A> serde::de::MapAccess<'de> for FieldsMapAccess<'_, A>
+where
+    A: serde::de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        while let Some(key) = self.map.next_key::<String>()? {
+            let is_field = VARIABLE_FIELDS.contains(&key.as_str())
+                && (self.keys.has_fields || self.keys.first_nested.is_none());
+            if is_field {
+                self.keys.has_fields = true;
+                return seed.deserialize(key.into_deserializer()).map(Some);
+            }
+            if key.starts_with('$') {
+                self.section.add_directive(&key, &mut self.map)?;
+                self.keys.first_nested.get_or_insert(key);
+            } else if let Some(entry) = self.map.next_value::<NestedEntry>()?.0 {
+                self.section.add_entry(key.clone(), entry);
+                self.keys.first_nested.get_or_insert(key);
+            } else {
+                self.keys.first_unknown.get_or_insert(key);
+            }
         }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+}
+
+// The value of a key which is not a field: a map is an entry of a section, any other value is
+// ignored, since the key is reported as an unknown field.
+struct NestedEntry(Option<DeclarationEntry>);
+
+impl<'de> Deserialize<'de> for NestedEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NestedEntryVisitor {})
+    }
+}
+
+struct NestedEntryVisitor {}
+
+impl<'de> Visitor<'de> for NestedEntryVisitor {
+    type Value = NestedEntry;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a variable declaration, a section or a field value")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        DeclarationEntryVisitor {}
+            .visit_map(map)
+            .map(|it| NestedEntry(Some(it)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_bool<E>(self, _v: bool) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_i64<E>(self, _v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_u64<E>(self, _v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_f64<E>(self, _v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_str<E>(self, _v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NestedEntry(None))
+    }
+}
+
+struct IncludeDirective(Vec<String>);
+
+impl<'de> Deserialize<'de> for IncludeDirective {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(IncludeDirectiveVisitor {})
+    }
+}
+
+struct IncludeDirectiveVisitor {}
+
+impl<'de> Visitor<'de> for IncludeDirectiveVisitor {
+    type Value = IncludeDirective;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a path or a list of paths")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(IncludeDirective(vec![v.to_string()]))
+    }
 
-        Ok(values)
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut paths = Vec::new();
+        while let Some(path) = seq.next_element()? {
+            paths.push(path);
+        }
+        Ok(IncludeDirective(paths))
     }
 }
 
+// The fields are optional so that a file can override only some of the fields of a variable
+// declared in an included file; complete declarations are checked by `validate`.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct VariableConfiguration {
     #[serde(rename(deserialize = "type"))]
-    rust_type: AcceptedRustType,
+    rust_type: Option<AcceptedRustType>,
     // This field is used to require a description for a variable, and for future uses in case
     // it will be possible to add documentation comments to a generated token stream.
-    #[allow(dead_code)]
-    description: String,
-    #[serde(rename(deserialize = "default"))]
+    description: Option<String>,
+    #[serde(
+        rename(deserialize = "default"),
+        default,
+        deserialize_with = "deserialize_default_value"
+    )]
     default_value: Option<String>,
+    required: Option<bool>,
+    separator: Option<String>,
+    trim: Option<bool>,
+    values: Option<Vec<String>>,
+    case_insensitive: Option<bool>,
+    expand_tilde: Option<bool>,
+    base_dir: Option<String>,
+    schemes: Option<Vec<String>>,
+    parser: Option<String>,
+    truthy: Option<Vec<String>>,
+    falsy: Option<Vec<String>>,
+    secret: Option<bool>,
+    file_fallback: Option<bool>,
+}
+
+fn deserialize_default_value<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    // Formats with typed scalars (e.g. toml) only accept strings in quotes, but the default value
+    // is always parsed by the generated code, so numbers and booleans are kept as written; a null
+    // value (e.g. `~` in yaml) is no default value.
+    deserializer.deserialize_option(DefaultValueVisitor::new())
+}
+
+struct DefaultValueVisitor {}
+
+impl DefaultValueVisitor {
+    fn new() -> Self {
+        DefaultValueVisitor {}
+    }
+}
+
+impl<'de> Visitor<'de> for DefaultValueVisitor {
+    type Value = Option<String>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a string, a number or a boolean")
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_string(self)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Some(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Some(v))
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Some(v.to_string()))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Some(v.to_string()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Some(v.to_string()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Some(v.to_string()))
+    }
 }
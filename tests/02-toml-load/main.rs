@@ -0,0 +1,13 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/02-toml-load/spec.toml", format = "toml")]
+struct MyConfig;
+
+#[test]
+fn test_toml_defaults() {
+    temp_env::with_vars_unset(["TEST_VAR", "TEST_NAME"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.TEST_VAR(), 0);
+        assert_eq!(config.TEST_NAME(), "test");
+    });
+}
@@ -0,0 +1,15 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/03-yaml-load/spec.yaml", format = "yaml")]
+struct MyConfig;
+
+#[test]
+fn test_yaml_defaults() {
+    temp_env::with_vars_unset(["TEST_VAR", "TEST_NAME", "TEST_FLAG", "TEST_LABEL"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.TEST_VAR(), 0);
+        assert_eq!(config.TEST_NAME(), "test");
+        assert!(!config.TEST_FLAG());
+        assert_eq!(config.TEST_LABEL(), None);
+    });
+}
@@ -0,0 +1,19 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/04-json-load/spec.json", format = "json")]
+struct JsonConfig;
+
+#[declarative_env(path = "./tests/04-json-load/spec.json5", format = "json5")]
+struct Json5Config;
+
+#[test]
+fn test_json_defaults() {
+    temp_env::with_vars_unset(["TEST_VAR", "TEST_NAME"], || {
+        let json_config = JsonConfig::from_env().expect("defaults are valid");
+        assert_eq!(json_config.TEST_VAR(), 0);
+        assert_eq!(json_config.TEST_NAME(), "test");
+        let json5_config = Json5Config::from_env().expect("defaults are valid");
+        assert_eq!(json5_config.TEST_VAR(), 0);
+        assert_eq!(json5_config.TEST_NAME(), "test");
+    });
+}
@@ -0,0 +1,19 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/05-inferred-format/spec.yml")]
+struct MyConfig;
+
+#[declarative_env(path = "./tests/05-inferred-format/.environment.hjson")]
+struct HjsonConfig;
+
+#[declarative_env(path = "./tests/05-inferred-format/.env.local")]
+struct DotenvConfig;
+
+#[test]
+fn test_inferred_formats() {
+    temp_env::with_vars_unset(["TEST_VAR", "TEST_NAME", "TEST_FLAG"], || {
+        assert_eq!(MyConfig::from_env().expect("defaults are valid").TEST_VAR(), 0);
+        assert_eq!(HjsonConfig::from_env().expect("defaults are valid").TEST_NAME(), "test");
+        assert!(DotenvConfig::from_env().expect("defaults are valid").TEST_FLAG());
+    });
+}
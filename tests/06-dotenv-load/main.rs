@@ -0,0 +1,28 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/06-dotenv-load/.env.example")]
+struct MyConfig;
+
+#[test]
+fn test_dotenv_defaults() {
+    temp_env::with_vars(
+        [
+            ("SERVER_PORT", None),
+            ("LOG_LEVEL", None),
+            ("API_TOKEN", Some("token")),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("defaults are valid");
+            assert_eq!(config.SERVER_PORT(), 8080);
+            assert_eq!(config.LOG_LEVEL(), "info");
+            assert_eq!(config.API_TOKEN(), "token");
+        },
+    );
+}
+
+#[test]
+fn test_dotenv_required_variable() {
+    temp_env::with_vars_unset(["SERVER_PORT", "LOG_LEVEL", "API_TOKEN"], || {
+        assert!(MyConfig::from_env().is_err());
+    });
+}
@@ -0,0 +1,18 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(inline = {
+    SERVER_PORT: { type: u16, description: "The port the server will listen on", default: 8080 },
+    "LOG_LEVEL": { type: String, description: "The minimum level of the logs", default: info },
+    OFFSET: { type: i32, description: "A negative default", default: -5 },
+})]
+struct MyConfig;
+
+#[test]
+fn test_inline_defaults() {
+    temp_env::with_vars_unset(["SERVER_PORT", "LOG_LEVEL", "OFFSET"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.SERVER_PORT(), 8080);
+        assert_eq!(config.LOG_LEVEL(), "info");
+        assert_eq!(config.OFFSET(), -5);
+    });
+}
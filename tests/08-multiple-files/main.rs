@@ -0,0 +1,16 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = [
+    "./tests/08-multiple-files/common.hjson",
+    "./tests/08-multiple-files/service.env",
+])]
+struct MyConfig;
+
+#[test]
+fn test_multiple_files_defaults() {
+    temp_env::with_vars_unset(["LOG_LEVEL", "SERVER_PORT"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.LOG_LEVEL(), "info");
+        assert_eq!(config.SERVER_PORT(), 8080);
+    });
+}
@@ -0,0 +1,15 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/09-include/spec.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_include_overrides() {
+    temp_env::with_vars_unset(["APP_NAME", "LOG_LEVEL", "LOG_FORMAT", "SERVER_PORT"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.APP_NAME(), "service");
+        assert_eq!(config.LOG_LEVEL(), "debug");
+        assert_eq!(config.LOG_FORMAT(), "json");
+        assert_eq!(config.SERVER_PORT(), 8080);
+    });
+}
@@ -0,0 +1,17 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/10-sections/env.hjson", section = "server")]
+struct ServerConfig;
+
+#[declarative_env(path = "./tests/10-sections/env.hjson", section = "database.pool")]
+struct PoolConfig;
+
+#[test]
+fn test_sections_defaults() {
+    temp_env::with_vars_unset(["SERVER_PORT", "DB_POOL_SIZE"], || {
+        let server_config = ServerConfig::from_env().expect("defaults are valid");
+        assert_eq!(server_config.SERVER_PORT(), 8080);
+        let pool_config = PoolConfig::from_env().expect("defaults are valid");
+        assert_eq!(pool_config.DB_POOL_SIZE(), 10);
+    });
+}
@@ -0,0 +1,13 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "$WORKSPACE/tests/11-path-anchors/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_path_anchors_defaults() {
+    temp_env::with_vars_unset(["APP_NAME", "SERVER_PORT"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.APP_NAME(), "anchors");
+        assert_eq!(config.SERVER_PORT(), 8080);
+    });
+}
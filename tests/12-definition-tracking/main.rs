@@ -0,0 +1,13 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = ["./tests/12-definition-tracking/server.env", "./tests/12-definition-tracking/logging.env"])]
+struct MyConfig;
+
+#[test]
+fn test_definition_tracking_defaults() {
+    temp_env::with_vars_unset(["SERVER_PORT", "LOG_LEVEL"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.SERVER_PORT(), 8080);
+        assert_eq!(config.LOG_LEVEL(), "info");
+    });
+}
@@ -0,0 +1,30 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/13-optional-variables/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_optional_variables_missing() {
+    temp_env::with_vars_unset(["SENTRY_DSN", "WORKERS", "SERVER_PORT"], || {
+        let config = MyConfig::from_env().expect("optional variables can be missing");
+        assert_eq!(config.SENTRY_DSN(), None);
+        assert_eq!(config.WORKERS(), None);
+        assert_eq!(config.SERVER_PORT(), 8080);
+    });
+}
+
+#[test]
+fn test_optional_variables_set() {
+    temp_env::with_vars(
+        [
+            ("SENTRY_DSN", Some("https://key@sentry.example.com/1")),
+            ("WORKERS", Some("4")),
+            ("SERVER_PORT", None),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("optional variables are valid");
+            assert_eq!(config.SENTRY_DSN(), Some("https://key@sentry.example.com/1"));
+            assert_eq!(config.WORKERS(), Some(4));
+        },
+    );
+}
@@ -0,0 +1,48 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/14-list-variables/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_list_variables_defaults() {
+    temp_env::with_vars_unset(["ALLOWED_ORIGINS", "KAFKA_BROKERS", "RETRY_DELAYS"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(
+            config.ALLOWED_ORIGINS(),
+            ["https://example.com", "https://example.org"]
+        );
+        assert_eq!(config.KAFKA_BROKERS(), None);
+        assert_eq!(config.RETRY_DELAYS(), [100, 200, 400]);
+    });
+}
+
+#[test]
+fn test_list_variables_set() {
+    temp_env::with_vars(
+        [
+            ("ALLOWED_ORIGINS", Some("")),
+            ("KAFKA_BROKERS", Some("h1:9092 h2:9092")),
+            ("RETRY_DELAYS", Some("10;20")),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("values are valid");
+            assert!(config.ALLOWED_ORIGINS().is_empty());
+            assert_eq!(
+                config.KAFKA_BROKERS(),
+                Some(&["h1:9092".to_string(), "h2:9092".to_string()][..])
+            );
+            assert_eq!(config.RETRY_DELAYS(), [10, 20]);
+        },
+    );
+}
+
+#[test]
+fn test_list_variables_invalid_element() {
+    temp_env::with_vars([("RETRY_DELAYS", Some("10;twenty"))], || {
+        let error = MyConfig::from_env().expect_err("the second element is not a number");
+        assert_eq!(
+            error.to_string(),
+            "failed to load configuration from env: invalid value '10;twenty' for variable RETRY_DELAYS: element 1: invalid digit found in string"
+        );
+    });
+}
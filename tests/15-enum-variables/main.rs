@@ -0,0 +1,37 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/15-enum-variables/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_enum_variables_defaults() {
+    temp_env::with_vars_unset(["LOG_FORMAT", "APP_ENV"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.LOG_FORMAT(), MyConfigLogFormat::Json);
+        assert_eq!(config.LOG_FORMAT().to_string(), "json");
+        assert_eq!(config.APP_ENV(), None);
+    });
+}
+
+#[test]
+fn test_enum_variables_case_insensitive() {
+    temp_env::with_vars(
+        [("LOG_FORMAT", Some("pretty")), ("APP_ENV", Some("PROD"))],
+        || {
+            let config = MyConfig::from_env().expect("values are valid");
+            assert_eq!(config.LOG_FORMAT(), MyConfigLogFormat::Pretty);
+            assert_eq!(config.APP_ENV(), Some(MyConfigAppEnv::Prod));
+        },
+    );
+}
+
+#[test]
+fn test_enum_variables_invalid_value() {
+    temp_env::with_vars([("LOG_FORMAT", Some("Pretty")), ("APP_ENV", None)], || {
+        let error = MyConfig::from_env().expect_err("the value is case sensitive");
+        assert_eq!(
+            error.to_string(),
+            "failed to load configuration from env: invalid value 'Pretty' for variable LOG_FORMAT: expected one of 'json', 'pretty', 'compact'"
+        );
+    });
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/16-duration-variables/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_duration_variables_defaults() {
+    temp_env::with_vars_unset(["REQUEST_TIMEOUT", "POLL_INTERVAL", "RETRY_BACKOFF"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.REQUEST_TIMEOUT(), Duration::from_secs(5400));
+        assert_eq!(config.POLL_INTERVAL(), Duration::from_millis(250));
+        assert_eq!(
+            config.RETRY_BACKOFF(),
+            [
+                Duration::from_secs(1),
+                Duration::from_secs(5),
+                Duration::from_secs(30)
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_duration_variables_invalid_value() {
+    temp_env::with_vars(
+        [
+            ("REQUEST_TIMEOUT", Some("30")),
+            ("POLL_INTERVAL", None),
+            ("RETRY_BACKOFF", None),
+        ],
+        || {
+            let error = MyConfig::from_env().expect_err("the unit is missing");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value '30' for variable REQUEST_TIMEOUT: missing unit after '30', expected one of ns, us, ms, s, m, h, d"
+            );
+        },
+    );
+}
+
+#[test]
+fn test_duration_variables_zero() {
+    temp_env::with_vars(
+        [
+            ("REQUEST_TIMEOUT", Some("0")),
+            ("POLL_INTERVAL", None),
+            ("RETRY_BACKOFF", Some("0,1s")),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("a zero duration needs no unit");
+            assert_eq!(config.REQUEST_TIMEOUT(), Duration::ZERO);
+            assert_eq!(
+                config.RETRY_BACKOFF(),
+                [Duration::ZERO, Duration::from_secs(1)]
+            );
+        },
+    );
+}
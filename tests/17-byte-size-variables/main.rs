@@ -0,0 +1,39 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/17-byte-size-variables/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_byte_size_variables_defaults() {
+    temp_env::with_vars_unset(["MAX_BODY_SIZE", "CACHE_CAPACITY"], || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.MAX_BODY_SIZE(), 10_000_000);
+        assert_eq!(config.CACHE_CAPACITY(), 2 * 1024 * 1024 * 1024);
+    });
+}
+
+#[test]
+fn test_byte_size_variables_set() {
+    temp_env::with_vars(
+        [("MAX_BODY_SIZE", Some("512")), ("CACHE_CAPACITY", Some("64 KiB"))],
+        || {
+            let config = MyConfig::from_env().expect("values are valid");
+            assert_eq!(config.MAX_BODY_SIZE(), 512);
+            assert_eq!(config.CACHE_CAPACITY(), 64 * 1024);
+        },
+    );
+}
+
+#[test]
+fn test_byte_size_variables_invalid_value() {
+    temp_env::with_vars(
+        [("MAX_BODY_SIZE", Some("10mb")), ("CACHE_CAPACITY", None)],
+        || {
+            let error = MyConfig::from_env().expect_err("the unit is case sensitive");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value '10mb' for variable MAX_BODY_SIZE: unknown unit 'mb', expected one of B, kB, MB, GB, TB, PB, KiB, MiB, GiB, TiB, PiB"
+            );
+        },
+    );
+}
@@ -0,0 +1,75 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/18-network-variables/env.hjson")]
+struct MyConfig;
+
+const VARIABLES: [&str; 5] = [
+    "BIND_ADDRESS",
+    "ADMIN_IP",
+    "DNS_SERVER",
+    "DATABASE_HOST",
+    "KAFKA_BROKERS",
+];
+
+#[test]
+fn test_network_variables_defaults() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(
+            config.BIND_ADDRESS(),
+            SocketAddr::from((Ipv4Addr::UNSPECIFIED, 8080))
+        );
+        assert_eq!(config.ADMIN_IP(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!(config.DNS_SERVER(), Ipv4Addr::new(1, 1, 1, 1));
+        assert_eq!(config.DATABASE_HOST().host(), "db.internal");
+        assert_eq!(config.DATABASE_HOST().port(), 5432);
+        let brokers: Vec<String> = config
+            .KAFKA_BROKERS()
+            .iter()
+            .map(|it| it.to_string())
+            .collect();
+        assert_eq!(brokers, ["h1:9092", "[::1]:9093"]);
+    });
+}
+
+#[test]
+fn test_network_variables_invalid_value() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("BIND_ADDRESS", Some("localhost:8080"), || {
+            let error = MyConfig::from_env().expect_err("the host is not an IP address");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value 'localhost:8080' for variable BIND_ADDRESS: expected an IP address and a port (e.g. '0.0.0.0:8080' or '[::1]:8080')"
+            );
+        });
+        temp_env::with_var("DATABASE_HOST", Some("db-.internal:5432"), || {
+            let error = MyConfig::from_env().expect_err("the host is not a DNS name");
+            assert!(error
+                .to_string()
+                .contains("invalid host 'db-.internal', expected 'host:port'"));
+        });
+        temp_env::with_var("DATABASE_HOST", Some("999.1.1.1:80"), || {
+            let error = MyConfig::from_env().expect_err("the host is not an IPv4 address");
+            assert!(error
+                .to_string()
+                .contains("invalid host '999.1.1.1', expected 'host:port'"));
+        });
+    });
+}
+
+#[test]
+fn test_network_variables_container_names() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("KAFKA_BROKERS", Some("kafka_1:9092 kafka_2:9092"), || {
+            let config = MyConfig::from_env().expect("underscores are allowed in the host");
+            let brokers: Vec<String> = config
+                .KAFKA_BROKERS()
+                .iter()
+                .map(|it| it.to_string())
+                .collect();
+            assert_eq!(brokers, ["kafka_1:9092", "kafka_2:9092"]);
+        });
+    });
+}
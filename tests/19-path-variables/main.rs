@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/19-path-variables/env.hjson")]
+struct MyConfig;
+
+#[test]
+fn test_path_variables_defaults() {
+    temp_env::with_vars(
+        [
+            ("DATA_DIR", None),
+            ("CACHE_DIR", None),
+            ("CONFIG_FILE", None),
+            ("HOME", Some("/home/app")),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("defaults are valid");
+            assert_eq!(config.DATA_DIR(), Path::new("/var/lib/app/data"));
+            assert_eq!(config.CACHE_DIR(), Path::new("/home/app/.cache/app"));
+            assert_eq!(config.CONFIG_FILE(), None);
+        },
+    );
+}
+
+#[test]
+fn test_path_variables_set() {
+    temp_env::with_vars(
+        [
+            ("DATA_DIR", Some("/srv/data")),
+            ("CACHE_DIR", Some("/tmp/cache")),
+            ("CONFIG_FILE", Some("app.toml")),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("values are valid");
+            assert_eq!(config.DATA_DIR(), Path::new("/srv/data"));
+            assert_eq!(config.CACHE_DIR(), Path::new("/tmp/cache"));
+            assert_eq!(config.CONFIG_FILE(), Some(Path::new("app.toml")));
+        },
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_variables_not_unicode() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let not_unicode = OsStr::from_bytes(b"/srv/\xff");
+    temp_env::with_vars(
+        [
+            ("DATA_DIR", Some(not_unicode)),
+            ("CACHE_DIR", None),
+            ("CONFIG_FILE", None),
+        ],
+        || {
+            let config = MyConfig::from_env().expect("paths can be any OS string");
+            assert_eq!(config.DATA_DIR().as_os_str(), not_unicode);
+        },
+    );
+}
@@ -0,0 +1,46 @@
+use std::num::{NonZeroU32, NonZeroUsize};
+
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/21-primitive-types/env.hjson")]
+struct MyConfig;
+
+const VARIABLES: [&str; 5] = [
+    "WORKER_THREADS",
+    "MAX_CONNECTIONS",
+    "QUEUE_CAPACITY",
+    "CLOCK_SKEW",
+    "CSV_DELIMITER",
+];
+
+#[test]
+fn test_primitive_types_defaults() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.WORKER_THREADS(), NonZeroUsize::new(4).unwrap());
+        assert_eq!(config.MAX_CONNECTIONS(), NonZeroU32::new(128).unwrap());
+        assert_eq!(config.QUEUE_CAPACITY(), 1024usize);
+        assert_eq!(config.CLOCK_SKEW(), -2isize);
+        assert_eq!(config.CSV_DELIMITER(), ';');
+    });
+}
+
+#[test]
+fn test_primitive_types_invalid_value() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("WORKER_THREADS", Some("0"), || {
+            let error = MyConfig::from_env().expect_err("zero is not a valid NonZeroUsize");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value '0' for variable WORKER_THREADS: number would be zero for non-zero type"
+            );
+        });
+        temp_env::with_var("CSV_DELIMITER", Some("||"), || {
+            let error = MyConfig::from_env().expect_err("two characters are not a char");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value '||' for variable CSV_DELIMITER: too many characters in string"
+            );
+        });
+    });
+}
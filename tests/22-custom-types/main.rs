@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use declarative_env::declarative_env;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Region {
+    EuWest1,
+    UsEast1,
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "eu-west-1" => Ok(Region::EuWest1),
+            "us-east-1" => Ok(Region::UsEast1),
+            _ => Err(format!("unknown region '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantId(String);
+
+impl FromStr for TenantId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("tenant-") {
+            Ok(TenantId(s.to_string()))
+        } else {
+            Err(String::from("expected a 'tenant-' prefix"))
+        }
+    }
+}
+
+#[declarative_env(path = "./tests/22-custom-types/env.hjson")]
+struct MyConfig;
+
+const VARIABLES: [&str; 3] = ["AWS_REGION", "REPLICA_REGIONS", "TENANT_ID"];
+
+#[test]
+fn test_custom_types_defaults() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(config.AWS_REGION(), &Region::EuWest1);
+        assert_eq!(
+            config.REPLICA_REGIONS(),
+            &[Region::UsEast1, Region::EuWest1]
+        );
+        assert_eq!(config.TENANT_ID(), None);
+    });
+}
+
+#[test]
+fn test_custom_types_from_env() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("TENANT_ID", Some("tenant-42"), || {
+            let config = MyConfig::from_env().expect("the tenant id is valid");
+            assert_eq!(
+                config.TENANT_ID(),
+                Some(&TenantId(String::from("tenant-42")))
+            );
+        });
+    });
+}
+
+#[test]
+fn test_custom_types_invalid_value() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("AWS_REGION", Some("mars-1"), || {
+            let error = MyConfig::from_env().expect_err("the region is unknown");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value 'mars-1' for variable AWS_REGION: unknown region 'mars-1'"
+            );
+        });
+    });
+}
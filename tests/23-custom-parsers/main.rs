@@ -0,0 +1,67 @@
+use declarative_env::declarative_env;
+
+mod config {
+    use std::num::ParseIntError;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Weights(pub Vec<(String, u32)>);
+
+    pub fn parse_weights(value: &str) -> Result<Weights, String> {
+        value
+            .split(',')
+            .map(|entry| match entry.split_once('=') {
+                Some((key, weight)) => weight
+                    .parse()
+                    .map(|weight| (key.to_string(), weight))
+                    .map_err(|_| format!("invalid weight '{}'", weight)),
+                None => Err(format!("expected 'key=weight', found '{}'", entry)),
+            })
+            .collect::<Result<Vec<(String, u32)>, String>>()
+            .map(Weights)
+    }
+
+    pub fn parse_hex(value: &str) -> Result<u32, ParseIntError> {
+        u32::from_str_radix(value.trim_start_matches("0x"), 16)
+    }
+}
+
+#[declarative_env(path = "./tests/23-custom-parsers/env.hjson")]
+struct MyConfig;
+
+const VARIABLES: [&str; 3] = ["ROUTING_WEIGHTS", "FEATURE_MASK", "SHARD_MASKS"];
+
+#[test]
+fn test_custom_parsers_defaults() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert_eq!(
+            config.ROUTING_WEIGHTS(),
+            &config::Weights(vec![
+                (String::from("primary"), 3),
+                (String::from("canary"), 1)
+            ])
+        );
+        assert_eq!(config.FEATURE_MASK(), 0xff);
+        assert_eq!(config.SHARD_MASKS(), &[0x0f, 0xf0]);
+    });
+}
+
+#[test]
+fn test_custom_parsers_invalid_value() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("ROUTING_WEIGHTS", Some("primary"), || {
+            let error = MyConfig::from_env().expect_err("the weight is missing");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value 'primary' for variable ROUTING_WEIGHTS: expected 'key=weight', found 'primary'"
+            );
+        });
+        temp_env::with_var("SHARD_MASKS", Some("0x0f,0xzz"), || {
+            let error = MyConfig::from_env().expect_err("the second mask is not hexadecimal");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value '0x0f,0xzz' for variable SHARD_MASKS: element 1: invalid digit found in string"
+            );
+        });
+    });
+}
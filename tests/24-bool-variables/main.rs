@@ -0,0 +1,82 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/24-bool-variables/env.hjson")]
+struct MyConfig;
+
+#[declarative_env(
+    path = "./tests/24-bool-variables/env.hjson",
+    bool_truthy = ["y", "on"],
+    bool_falsy = ["n", "false"]
+)]
+struct ShortConfig;
+
+const VARIABLES: [&str; 3] = ["ENABLE_METRICS", "DRY_RUN", "MAINTENANCE_MODE"];
+
+#[test]
+fn test_bool_variables_defaults() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        let config = MyConfig::from_env().expect("defaults are valid");
+        assert!(config.ENABLE_METRICS());
+        assert!(!config.DRY_RUN());
+        assert!(!config.MAINTENANCE_MODE());
+    });
+}
+
+#[test]
+fn test_bool_variables_lenient_values() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_vars(
+            [
+                ("ENABLE_METRICS", Some("NO")),
+                ("DRY_RUN", Some("1")),
+                ("MAINTENANCE_MODE", Some("Enabled")),
+            ],
+            || {
+                let config = MyConfig::from_env().expect("the values are valid");
+                assert!(!config.ENABLE_METRICS());
+                assert!(config.DRY_RUN());
+                assert!(config.MAINTENANCE_MODE());
+            },
+        );
+    });
+}
+
+#[test]
+fn test_bool_variables_invalid_value() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("DRY_RUN", Some("maybe"), || {
+            let error = MyConfig::from_env().expect_err("the value is neither true nor false");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value 'maybe' for variable DRY_RUN: expected one of 'true', '1', 'yes', 'on' for true or one of 'false', '0', 'no', 'off' for false"
+            );
+        });
+        temp_env::with_var("MAINTENANCE_MODE", Some("true"), || {
+            let error = MyConfig::from_env().expect_err("the variable has its own values");
+            assert!(error
+                .to_string()
+                .ends_with("expected one of 'enabled' for true or one of 'disabled' for false"));
+        });
+    });
+}
+
+#[test]
+fn test_bool_variables_macro_values() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_vars(
+            [("ENABLE_METRICS", Some("Y")), ("DRY_RUN", Some("n"))],
+            || {
+                let config = ShortConfig::from_env().expect("the values are valid");
+                assert!(config.ENABLE_METRICS());
+                assert!(!config.DRY_RUN());
+                assert!(!config.MAINTENANCE_MODE());
+            },
+        );
+        temp_env::with_var("ENABLE_METRICS", Some("yes"), || {
+            let error = ShortConfig::from_env().expect_err("'yes' is not truthy anymore");
+            assert!(error
+                .to_string()
+                .ends_with("expected one of 'y', 'on' for true or one of 'n', 'false' for false"));
+        });
+    });
+}
@@ -0,0 +1,52 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/25-secret-variables/env.hjson")]
+struct MyConfig;
+
+const VARIABLES: [&str; 3] = ["DB_PASSWORD", "API_TOKEN", "DB_USER"];
+
+#[test]
+fn test_secret_variables_expose() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("DB_PASSWORD", Some("hunter2"), || {
+            let config = MyConfig::from_env().expect("the password is set");
+            assert_eq!(config.DB_PASSWORD().expose(), "hunter2");
+            assert!(config.API_TOKEN().is_none());
+            assert_eq!(config.DB_USER(), "app");
+        });
+    });
+}
+
+#[test]
+fn test_secret_variables_redacted() {
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_vars(
+            [("DB_PASSWORD", Some("hunter2")), ("API_TOKEN", Some("tok_123"))],
+            || {
+                let config = MyConfig::from_env().expect("the secrets are set");
+                assert_eq!(config.DB_PASSWORD().to_string(), "[REDACTED]");
+                assert_eq!(format!("{:?}", config.API_TOKEN()), "Some([REDACTED])");
+                let debug = format!("{:?}", config);
+                assert!(!debug.contains("hunter2"));
+                assert!(!debug.contains("tok_123"));
+                assert!(debug.contains("DB_PASSWORD: [REDACTED]"));
+            },
+        );
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn test_secret_variables_not_unicode() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("DB_PASSWORD", Some(OsStr::from_bytes(b"hunter\xff")), || {
+            let error = MyConfig::from_env().expect_err("the password is not valid unicode");
+            assert_eq!(
+                error.to_string(),
+                "failed to load configuration from env: invalid value '[REDACTED]' for variable DB_PASSWORD: the value is not valid unicode"
+            );
+        });
+    });
+}
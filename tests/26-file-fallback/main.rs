@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/26-file-fallback/env.hjson")]
+struct MyConfig;
+
+#[declarative_env(path = "./tests/26-file-fallback/env.hjson", file_fallback = true)]
+struct GlobalConfig;
+
+const VARIABLES: [&str; 4] = ["DB_PASSWORD", "DB_PASSWORD_FILE", "WORKERS", "WORKERS_FILE"];
+
+fn secret_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "declarative-env-{}-{}",
+        std::process::id(),
+        name
+    ));
+    std::fs::write(&path, content).expect("the temp dir is writable");
+    path
+}
+
+#[test]
+fn test_file_fallback_reads_file() {
+    let path = secret_file("db-password", "hunter2\n");
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_vars(
+            [
+                ("DB_PASSWORD_FILE", Some(path.as_os_str())),
+                ("WORKERS_FILE", Some(path.as_os_str())),
+            ],
+            || {
+                let config = MyConfig::from_env().expect("the password file exists");
+                assert_eq!(config.DB_PASSWORD().expose(), "hunter2");
+                assert_eq!(config.WORKERS(), 4);
+            },
+        );
+        temp_env::with_var("DB_PASSWORD", Some("from-env"), || {
+            let config = MyConfig::from_env().expect("the password is set");
+            assert_eq!(config.DB_PASSWORD().expose(), "from-env");
+        });
+    });
+    std::fs::remove_file(path).expect("the file exists");
+}
+
+#[test]
+fn test_file_fallback_conflict() {
+    let path = secret_file("conflict", "hunter2\n");
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_vars(
+            [
+                ("DB_PASSWORD", Some(std::ffi::OsStr::new("from-env"))),
+                ("DB_PASSWORD_FILE", Some(path.as_os_str())),
+            ],
+            || {
+                let error = MyConfig::from_env().expect_err("both variables are set");
+                assert_eq!(
+                    error.to_string(),
+                    format!(
+                        "failed to load configuration from env: cannot read variable DB_PASSWORD from '{}': DB_PASSWORD is set as well, remove either DB_PASSWORD or DB_PASSWORD_FILE",
+                        path.display()
+                    )
+                );
+            },
+        );
+    });
+    std::fs::remove_file(path).expect("the file exists");
+}
+
+#[test]
+fn test_file_fallback_missing_file() {
+    let path = std::env::temp_dir().join("declarative-env-missing-secret");
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("DB_PASSWORD_FILE", Some(path.as_os_str()), || {
+            let error = MyConfig::from_env().expect_err("the file does not exist");
+            assert!(error.to_string().starts_with(&format!(
+                "failed to load configuration from env: cannot read variable DB_PASSWORD from '{}': ",
+                path.display()
+            )));
+        });
+    });
+}
+
+#[test]
+fn test_file_fallback_global() {
+    let password = secret_file("global-password", "hunter2\r\n");
+    let workers = secret_file("global-workers", "many\n");
+    temp_env::with_vars_unset(VARIABLES, || {
+        temp_env::with_var("DB_PASSWORD_FILE", Some(password.as_os_str()), || {
+            let config = GlobalConfig::from_env().expect("the password file exists");
+            assert_eq!(config.DB_PASSWORD().expose(), "hunter2");
+            assert_eq!(config.WORKERS(), 4);
+            temp_env::with_var("WORKERS_FILE", Some(workers.as_os_str()), || {
+                let error = GlobalConfig::from_env().expect_err("the file is not a number");
+                assert_eq!(
+                    error.to_string(),
+                    format!(
+                        "failed to load configuration from env: invalid value 'many' for variable WORKERS (read from '{}'): invalid digit found in string",
+                        workers.display()
+                    )
+                );
+            });
+        });
+    });
+    std::fs::remove_file(password).expect("the file exists");
+    std::fs::remove_file(workers).expect("the file exists");
+}
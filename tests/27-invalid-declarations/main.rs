@@ -0,0 +1,20 @@
+use declarative_env::declarative_env;
+
+#[declarative_env(path = "./tests/27-invalid-declarations/env.hjson")]
+struct MisspelledFieldConfig;
+
+#[declarative_env(inline = {
+    DATABASE_URL: { type: Url, description: "The URL of the database", default: "postgres:///db" },
+})]
+struct MissingHostConfig;
+
+#[declarative_env(inline = {
+    HOST_PORT: { type: enum, description: "The port of the host", values: ["http", "https"] },
+})]
+struct HelperTypeConflictConfig;
+
+#[declarative_env(inline = {
+    LOG_LEVEL: { type: enum, description: "The level of the logs", values: ["info", "debug"] },
+    LOG__LEVEL: { type: enum, description: "The level of the logs", values: ["info", "debug"] },
+})]
+struct EnumConflictConfig;
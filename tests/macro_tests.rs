@@ -15,22 +15,202 @@ struct CommandExecutionError {
 }
 
 fn check_expansion(test: &str, sequence: SequenceTree) -> Result<(), TestExpansionError> {
-    let output = Command::new("cargo").arg("expand").arg("--test").arg(test).output()?;
+    let output = Command::new("cargo").arg("expand").arg("--all-features").arg("--test").arg(test).output()?;
     if output.status.success() {
         let stdout = std::str::from_utf8(&output.stdout)?;
         let expanded_lines: Vec<String> = stdout.lines().map(|it| it.trim().to_owned()).collect();
         if sequence.accept(&expanded_lines) {
-            return Ok(());
+            Ok(())
         } else {
             panic!("sequence not found");
         }
     } else {
         let stderr = std::str::from_utf8(&output.stderr)?;
-        return Err(CommandExecutionError { stderr: stderr.to_owned() }.into())
+        Err(CommandExecutionError { stderr: stderr.to_owned() }.into())
     }
 }
 
+fn check_expansion_error(test: &str, message: &str) -> Result<(), TestExpansionError> {
+    let output = Command::new("cargo").arg("expand").arg("--all-features").arg("--test").arg(test).output()?;
+    let stderr = std::str::from_utf8(&output.stderr)?;
+    if output.status.success() {
+        panic!("the expansion did not fail");
+    } else if !stderr.contains(message) {
+        panic!("error not found: {}", stderr);
+    }
+    Ok(())
+}
+
 #[test]
 fn test_config_load() -> Result<(), TestExpansionError> {
     check_expansion("01-basic-load", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("TEST_VAR: u16,"), Line::trimmed("}")])))
 }
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("02-toml-load", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("TEST_VAR: u16,"), Line::trimmed("TEST_NAME: String,"), Line::trimmed("}")])))
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("03-yaml-load", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("TEST_VAR: u16,"), Line::trimmed("TEST_NAME: String,"), Line::trimmed("TEST_FLAG: bool,"), Line::trimmed("TEST_LABEL: Option<String>,"), Line::trimmed("}")])))
+}
+
+#[cfg(all(feature = "json", feature = "json5"))]
+#[test]
+fn test_json_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("04-json-load", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct JsonConfig {"), Line::trimmed("TEST_VAR: u16,"), Line::trimmed("TEST_NAME: String,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct Json5Config {"), Line::trimmed("TEST_VAR: u16,"), Line::trimmed("TEST_NAME: String,"), Line::trimmed("}")]))),
+    ))
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_inferred_format_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("05-inferred-format", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("TEST_VAR: u16,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_dotenv_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("06-dotenv-load", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("LOG_LEVEL: String,"), Line::trimmed("API_TOKEN: String,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_inline_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("07-inline-load", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("LOG_LEVEL: String,"), Line::trimmed("OFFSET: i32,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_multiple_files_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("08-multiple-files", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("LOG_LEVEL: String,"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_include_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("09-include", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("APP_NAME: String,"), Line::trimmed("LOG_LEVEL: String,"), Line::trimmed("LOG_FORMAT: String,"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_sections_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("10-sections", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct ServerConfig {"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct PoolConfig {"), Line::trimmed("DB_POOL_SIZE: u32,"), Line::trimmed("}")]))),
+    ))
+}
+
+#[test]
+fn test_path_anchors_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("11-path-anchors", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("APP_NAME: String,"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_definition_tracking_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("12-definition-tracking", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("const _: &[u8] = b\"# The port the server will listen on\\n# @type u16\\nSERVER_PORT=8080\\n\";"), Line::trimmed("const _: &[u8] = b\"# The minimum level of the logs\\nLOG_LEVEL=info\\n\";")])))
+}
+
+#[test]
+fn test_optional_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("13-optional-variables", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("SENTRY_DSN: Option<String>,"), Line::trimmed("WORKERS: Option<u16>,"), Line::trimmed("SERVER_PORT: u16,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_list_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("14-list-variables", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("ALLOWED_ORIGINS: Vec<String>,"), Line::trimmed("KAFKA_BROKERS: Option<Vec<String>>,"), Line::trimmed("RETRY_DELAYS: Vec<u16>,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_enum_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("15-enum-variables", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("LOG_FORMAT: MyConfigLogFormat,"), Line::trimmed("APP_ENV: Option<MyConfigAppEnv>,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("enum MyConfigLogFormat {"), Line::trimmed("Json,"), Line::trimmed("Pretty,"), Line::trimmed("Compact,"), Line::trimmed("}")]))),
+    ))
+}
+
+#[test]
+fn test_duration_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("16-duration-variables", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("REQUEST_TIMEOUT: std::time::Duration,"), Line::trimmed("POLL_INTERVAL: std::time::Duration,"), Line::trimmed("RETRY_BACKOFF: Vec<std::time::Duration>,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_byte_size_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("17-byte-size-variables", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("MAX_BODY_SIZE: u64,"), Line::trimmed("CACHE_CAPACITY: u64,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_network_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("18-network-variables", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("BIND_ADDRESS: std::net::SocketAddr,"), Line::trimmed("ADMIN_IP: std::net::IpAddr,"), Line::trimmed("DNS_SERVER: std::net::Ipv4Addr,"), Line::trimmed("DATABASE_HOST: MyConfigHostPort,"), Line::trimmed("KAFKA_BROKERS: Vec<MyConfigHostPort>,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfigHostPort {"), Line::trimmed("host: String,"), Line::trimmed("port: u16,"), Line::trimmed("}")]))),
+    ))
+}
+
+#[test]
+fn test_path_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("19-path-variables", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("DATA_DIR: std::path::PathBuf,"), Line::trimmed("CACHE_DIR: std::path::PathBuf,"), Line::trimmed("CONFIG_FILE: Option<std::path::PathBuf>,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_url_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("20-url-variables", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("DATABASE_URL: MyConfigUrl,"), Line::trimmed("OTEL_EXPORTER_ENDPOINT: Option<MyConfigUrl>,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfigUrl {"), Line::trimmed("url: String,"), Line::trimmed("redacted: String,")]))),
+    ))
+}
+
+#[test]
+fn test_primitive_types_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("21-primitive-types", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("WORKER_THREADS: std::num::NonZeroUsize,"), Line::trimmed("MAX_CONNECTIONS: std::num::NonZeroU32,"), Line::trimmed("QUEUE_CAPACITY: usize,"), Line::trimmed("CLOCK_SKEW: isize,"), Line::trimmed("CSV_DELIMITER: char,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_custom_types_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("22-custom-types", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("AWS_REGION: crate::Region,"), Line::trimmed("REPLICA_REGIONS: Vec<crate::Region>,"), Line::trimmed("TENANT_ID: Option<crate::TenantId>,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_custom_parsers_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("23-custom-parsers", SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("ROUTING_WEIGHTS: crate::config::Weights,"), Line::trimmed("FEATURE_MASK: u32,"), Line::trimmed("SHARD_MASKS: Vec<u32>,"), Line::trimmed("}")])))
+}
+
+#[test]
+fn test_bool_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("24-bool-variables", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("ENABLE_METRICS: bool,"), Line::trimmed("DRY_RUN: bool,"), Line::trimmed("MAINTENANCE_MODE: bool,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("if [\"enabled\"].iter().any(|it| it.eq_ignore_ascii_case(value)) {"), Line::trimmed("Ok(true)")]))),
+    ))
+}
+
+#[test]
+fn test_secret_variables_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("25-secret-variables", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("DB_PASSWORD: MyConfigSecret,"), Line::trimmed("API_TOKEN: Option<MyConfigSecret>,"), Line::trimmed("DB_USER: String,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfigSecret {"), Line::trimmed("value: String,"), Line::trimmed("}")]))),
+    ))
+}
+
+#[test]
+fn test_file_fallback_config_load() -> Result<(), TestExpansionError> {
+    check_expansion("26-file-fallback", SequenceTree::And(
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("struct MyConfig {"), Line::trimmed("DB_PASSWORD: MyConfigSecret,"), Line::trimmed("WORKERS: u16,"), Line::trimmed("}")]))),
+        Box::new(SequenceTree::Sequence(Sequence::new(vec![Line::trimmed("std::env::var_os(\"DB_PASSWORD\"),"), Line::trimmed("std::env::var_os(\"DB_PASSWORD_FILE\"),")]))),
+    ))
+}
+
+#[test]
+fn test_misspelled_field_error() -> Result<(), TestExpansionError> {
+    check_expansion_error("27-invalid-declarations", "unknown field `descripton`, expected one of `type`, `description`, `default`")
+}
+
+#[test]
+fn test_url_missing_host_error() -> Result<(), TestExpansionError> {
+    check_expansion_error("27-invalid-declarations", "invalid default value 'postgres:///db' for variable 'DATABASE_URL': missing host")
+}
+
+#[test]
+fn test_enum_name_conflict_errors() -> Result<(), TestExpansionError> {
+    check_expansion_error("27-invalid-declarations", "the enum of variable 'HOST_PORT' has the same name as the generated 'HostPort' type, rename the variable")?;
+    check_expansion_error("27-invalid-declarations", "the enum of variable 'LOG__LEVEL' has the same name as the enum of variable 'LOG_LEVEL', rename the variable")
+}